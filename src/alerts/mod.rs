@@ -0,0 +1,87 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::list::List;
+
+// Stress levels worth shouting about, unset means never
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Alerts {
+    pub total: Option<f32>,
+    pub task: Option<f32>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Alert {
+    Total {
+        stress: f32,
+        threshold: f32,
+    },
+    Task {
+        id: usize,
+        name: String,
+        stress: f32,
+        threshold: f32,
+    },
+}
+
+impl std::fmt::Display for Alert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Alert::Total { stress, threshold } => {
+                write!(f, "Overall stress {:.2} is over {:.2}", stress, threshold)
+            }
+            Alert::Task {
+                id,
+                name,
+                stress,
+                threshold,
+            } => write!(
+                f,
+                "{} \"{}\" stress {:.2} is over {:.2}",
+                id, name, stress, threshold
+            ),
+        }
+    }
+}
+
+impl List {
+    pub fn alerts(&self, alerts: &Alerts) -> Vec<Alert> {
+        let mut found = Vec::new();
+        if let Some(threshold) = alerts.total {
+            let stress = self.total_stress();
+            if stress >= threshold {
+                found.push(Alert::Total { stress, threshold });
+            }
+        }
+        if let Some(threshold) = alerts.task {
+            found.extend(
+                self.tasks
+                    .values()
+                    .map(|task| (task, self.stress(task.id)))
+                    .filter(|(_, stress)| *stress >= threshold)
+                    .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+                    .map(|(task, stress)| Alert::Task {
+                        id: task.id,
+                        name: task.name.clone(),
+                        stress,
+                        threshold,
+                    }),
+            );
+        }
+        found
+    }
+}
+
+pub fn print_warning(alerts: &[Alert]) {
+    if alerts.is_empty() {
+        return;
+    }
+    let banner = "!".repeat(40);
+    println!("{}", banner);
+    println!("!! Stress alert");
+    for alert in alerts {
+        println!("!!   {}", alert);
+    }
+    println!("{}", banner);
+}
@@ -0,0 +1,148 @@
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::{ApiError, ApiResult, Session};
+use crate::list::List;
+
+fn body<T: DeserializeOwned>(body: &str) -> ApiResult<T> {
+    serde_json::from_str(body).map_err(|error| ApiError::BadRequest(error.to_string()))
+}
+
+fn value<T: Serialize>(value: T) -> ApiResult<Value> {
+    Ok(serde_json::to_value(value).unwrap())
+}
+
+fn id(segment: &str) -> ApiResult<usize> {
+    segment
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("Invalid task id \"{}\"", segment)))
+}
+
+// Browsers can send simple cross site requests to localhost, but not with a
+// json content type, and a rebound dns name still carries its own host
+fn check(request: &Request, port: u16) -> Result<(), (u16, String)> {
+    let header = |name: &str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.as_str().as_str().eq_ignore_ascii_case(name))
+            .map(|header| header.value.as_str().to_string())
+    };
+    let host = header("Host").unwrap_or_default();
+    if host != format!("127.0.0.1:{}", port) && host != format!("localhost:{}", port) {
+        return Err((403, format!("Host \"{}\" is not allowed", host)));
+    }
+    if *request.method() != Method::Get {
+        let content_type = header("Content-Type").unwrap_or_default();
+        if content_type.split(';').next().unwrap().trim() != "application/json" {
+            return Err((415, String::from("Content-Type must be application/json")));
+        }
+    }
+    Ok(())
+}
+
+fn route(list: &mut List, method: &Method, path: &[&str], request: &str) -> ApiResult<Value> {
+    match (method, path) {
+        (Method::Get, ["tasks"]) => value(super::tasks(list)),
+        (Method::Post, ["tasks"]) => value(super::create_task(list, body(request)?)?),
+        (Method::Get, ["tasks", task]) => value(super::task(list, id(task)?)?),
+        (Method::Patch, ["tasks", task]) => {
+            value(super::update_task(list, id(task)?, body(request)?)?)
+        }
+        (Method::Delete, ["tasks", task]) => value(super::delete_task(list, id(task)?)?),
+        (Method::Post, ["tasks", task, "complete"]) => {
+            value(super::complete_task(list, id(task)?)?)
+        }
+        (Method::Post, ["tasks", task, "subtasks", subtask]) => {
+            value(super::link(list, id(task)?, id(subtask)?)?)
+        }
+        (Method::Delete, ["tasks", task, "subtasks", subtask]) => {
+            value(super::unlink(list, id(task)?, id(subtask)?)?)
+        }
+        (Method::Get, ["stress"]) => value(super::stress(list)),
+        (Method::Get, ["schedule", date]) => {
+            value(super::get_itinerary(list, super::parse_date(date)?))
+        }
+        (Method::Put, ["schedule", date]) => value(super::set_itinerary(
+            list,
+            super::parse_date(date)?,
+            body(request)?,
+        )?),
+        (Method::Delete, ["schedule", date]) => {
+            value(super::clear_itinerary(list, super::parse_date(date)?))
+        }
+        (Method::Get, ["default-schedule", weekday]) => value(super::get_default_itinerary(
+            list,
+            super::parse_weekday(weekday)?,
+        )),
+        (Method::Put, ["default-schedule", weekday]) => value(super::set_default_itinerary(
+            list,
+            super::parse_weekday(weekday)?,
+            body(request)?,
+        )?),
+        _ => Err(ApiError::NotFound(format!(
+            "No route for {} /{}",
+            method,
+            path.join("/")
+        ))),
+    }
+}
+
+pub fn serve(mut session: Session, port: u16) -> Result<()> {
+    // Only listen locally, there's no authentication
+    let server = Server::http(("127.0.0.1", port)).map_err(|error| anyhow!(error))?;
+    println!("Listening on http://127.0.0.1:{}", port);
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    for mut request in server.incoming_requests() {
+        let mut contents = String::new();
+        if request.as_reader().read_to_string(&mut contents).is_err() {
+            contents.clear();
+        }
+        let url = request.url().to_string();
+        let path = url
+            .split('?')
+            .next()
+            .unwrap()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        session.refresh();
+        session.begin();
+        let status = |error: ApiError| {
+            let status = match error {
+                ApiError::BadRequest(_) => 400,
+                ApiError::NotFound(_) => 404,
+                ApiError::Conflict(_) => 409,
+                ApiError::Storage(_) => 500,
+            };
+            (status, error.to_string())
+        };
+        let result = check(&request, port).and_then(|_| {
+            let response =
+                route(&mut session.list, request.method(), &path, &contents).map_err(status)?;
+            if *request.method() != Method::Get {
+                session.save().map_err(status)?;
+            }
+            Ok(response)
+        });
+        let (status, response) = match result {
+            Ok(response) => (200, response),
+            Err((status, error)) => {
+                // Drop anything a failed request managed to change
+                session.revert();
+                (status, json!({"error": error}))
+            }
+        };
+        let response = Response::from_string(response.to_string())
+            .with_status_code(status)
+            .with_header(content_type.clone());
+        if let Err(error) = request.respond(response) {
+            eprintln!("Failed to respond: {}", error);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,388 @@
+pub mod http;
+pub mod rpc;
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta, Weekday};
+use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeSet;
+use std::time::SystemTime;
+
+use crate::export::schema::{TaskRecord, TimeBlockRecord, blocks, itinerary};
+use crate::hooks::Hook;
+use crate::list::List;
+use crate::manager::{load, open};
+use crate::storage::Storage;
+use crate::task::{Priority, Status, Task};
+
+#[derive(Debug)]
+pub enum ApiError {
+    NotFound(String),
+    BadRequest(String),
+    Conflict(String),
+    // The data file couldn't be written
+    Storage(String),
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::NotFound(message)
+            | ApiError::BadRequest(message)
+            | ApiError::Conflict(message)
+            | ApiError::Storage(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+pub type ApiResult<T> = Result<T, ApiError>;
+
+// The list and the storage it came from, shared with anything else editing
+// the same data file
+pub struct Session {
+    storage: Box<dyn Storage>,
+    pub list: List,
+    modified: Option<SystemTime>,
+    // The list before the current request, restored if it fails
+    snapshot: Option<List>,
+}
+
+impl Session {
+    pub fn open() -> Self {
+        let (storage, list, _) = open();
+        let mut session = Session {
+            storage,
+            list,
+            modified: None,
+            snapshot: None,
+        };
+        session.modified = session.file_modified();
+        session
+    }
+
+    fn file_modified(&self) -> Option<SystemTime> {
+        std::fs::metadata(self.storage.path())
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    // Reloads if something else saved the data file, true if it did
+    pub fn refresh(&mut self) -> bool {
+        let modified = self.file_modified();
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        if let Some(mut list) = load(self.storage.as_mut()) {
+            list.hooks = std::mem::take(&mut self.list.hooks);
+            self.list = list;
+        }
+        self.modified = self.file_modified();
+        true
+    }
+
+    // Call before each request, so revert has something to go back to
+    pub fn begin(&mut self) {
+        self.snapshot = Some(self.list.clone());
+    }
+
+    // Back to the list as it was before the request
+    pub fn revert(&mut self) {
+        if let Some(list) = self.snapshot.take() {
+            self.list = list;
+        }
+    }
+
+    pub fn save(&mut self) -> ApiResult<()> {
+        self.list.commit();
+        self.storage
+            .save(&self.list)
+            .map_err(|error| ApiError::Storage(format!("Failed to save: {}", error)))?;
+        self.modified = self.file_modified();
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskView {
+    #[serde(flatten)]
+    pub task: TaskRecord,
+    pub stress: f32,
+    pub effective_hours: f32,
+    pub earliest_completion: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewTask {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub estimated_hours: f32,
+    #[serde(default)]
+    pub estimated_stress: Option<f32>,
+    #[serde(default)]
+    pub start: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub deadline: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub subtasks: Vec<usize>,
+    #[serde(default)]
+    pub supertasks: Vec<usize>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub status: Status,
+}
+
+// Missing fields are left alone, null clears the optional ones
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TaskPatch {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub estimated_hours: Option<f32>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub estimated_stress: Option<Option<f32>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub start: Option<Option<NaiveDateTime>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub deadline: Option<Option<NaiveDateTime>>,
+    pub tags: Option<BTreeSet<String>>,
+    pub priority: Option<Priority>,
+    pub status: Option<Status>,
+}
+
+fn nullable<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStress {
+    pub id: usize,
+    pub name: String,
+    pub stress: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StressView {
+    pub total: f32,
+    pub tasks: Vec<TaskStress>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ItineraryView {
+    // "scheduled" for a date with its own itinerary, "default" otherwise
+    pub source: &'static str,
+    pub timeblocks: Vec<TimeBlockRecord>,
+}
+
+pub(super) fn hours(hours: f32) -> ApiResult<TimeDelta> {
+    if hours.is_nan() || hours < 0.0 {
+        return Err(ApiError::BadRequest(String::from(
+            "estimated_hours must not be negative",
+        )));
+    }
+    Ok(TimeDelta::seconds((hours * 3600.0) as i64))
+}
+
+// Done and cancelled tasks leave the list, that goes through complete and delete
+fn open_status(status: Status) -> ApiResult<Status> {
+    if status.is_closed() {
+        return Err(ApiError::BadRequest(format!(
+            "status can't be set to {}, complete or delete the task instead",
+            status
+        )));
+    }
+    Ok(status)
+}
+
+pub(super) fn get(list: &List, id: usize) -> ApiResult<&Task> {
+    list.tasks
+        .get(&id)
+        .ok_or_else(|| ApiError::NotFound(format!("No task with id {}", id)))
+}
+
+pub fn task(list: &List, id: usize) -> ApiResult<TaskView> {
+    let task = get(list, id)?;
+    let effective_time = list.effective_time(id);
+    Ok(TaskView {
+        task: TaskRecord::from(task),
+        stress: list.stress(id),
+        effective_hours: effective_time.as_seconds_f32() / 3600.0,
+        earliest_completion: list.schedule.earliest_complete(effective_time, task.start),
+    })
+}
+
+pub fn tasks(list: &List) -> Vec<TaskView> {
+    list.tasks
+        .keys()
+        .sorted()
+        .map(|&id| task(list, id).unwrap())
+        .collect()
+}
+
+pub fn create_task(list: &mut List, new: NewTask) -> ApiResult<TaskView> {
+    for &other in new.subtasks.iter().chain(new.supertasks.iter()) {
+        get(list, other)?;
+    }
+    let mut task = Task::new(
+        new.name,
+        new.description,
+        hours(new.estimated_hours)?,
+        new.estimated_stress,
+        new.start,
+        new.deadline,
+    );
+    task.set_tags(new.tags);
+    task.priority = new.priority;
+    task.set_status(open_status(new.status)?);
+    let id = list.insert_task(task);
+    for subtask in new.subtasks {
+        link(list, id, subtask)?;
+    }
+    for supertask in new.supertasks {
+        link(list, supertask, id)?;
+    }
+    list.run_hook(Hook::Add, id);
+    self::task(list, id)
+}
+
+pub fn update_task(list: &mut List, id: usize, patch: TaskPatch) -> ApiResult<TaskView> {
+    let mut task = get(list, id)?.clone();
+    if let Some(name) = patch.name {
+        task.name = name;
+    }
+    if let Some(description) = patch.description {
+        task.description = description;
+    }
+    if let Some(estimated_hours) = patch.estimated_hours {
+        task.estimated_time = hours(estimated_hours)?;
+    }
+    if let Some(estimated_stress) = patch.estimated_stress {
+        task.estimated_stress = estimated_stress;
+    }
+    if let Some(start) = patch.start {
+        task.start = start;
+    }
+    if let Some(deadline) = patch.deadline {
+        task.deadline = deadline;
+    }
+    if let Some(tags) = patch.tags {
+        task.set_tags(tags);
+    }
+    if let Some(priority) = patch.priority {
+        task.priority = priority;
+    }
+    if let Some(status) = patch.status {
+        task.set_status(open_status(status)?);
+    }
+    list.replace_task(task);
+    list.run_hook(Hook::Modify, id);
+    self::task(list, id)
+}
+
+pub fn delete_task(list: &mut List, id: usize) -> ApiResult<()> {
+    get(list, id)?;
+    list.remove_task(id);
+    Ok(())
+}
+
+pub fn complete_task(list: &mut List, id: usize) -> ApiResult<()> {
+    get(list, id)?;
+    list.complete(id)
+        .map_err(|error| ApiError::Conflict(error.to_string()))
+}
+
+pub fn link(list: &mut List, id: usize, subtask: usize) -> ApiResult<()> {
+    get(list, id)?;
+    get(list, subtask)?;
+    if list.would_cycle(id, subtask) {
+        return Err(ApiError::Conflict(format!(
+            "Task {} is already above task {}",
+            subtask, id
+        )));
+    }
+    list.add_subtask(id, subtask);
+    Ok(())
+}
+
+pub fn unlink(list: &mut List, id: usize, subtask: usize) -> ApiResult<()> {
+    get(list, id)?;
+    get(list, subtask)?;
+    list.remove_subtask(id, subtask);
+    Ok(())
+}
+
+pub fn stress(list: &List) -> StressView {
+    StressView {
+        total: list.total_stress(),
+        tasks: list
+            .tasks
+            .values()
+            .map(|task| TaskStress {
+                id: task.id,
+                name: task.name.clone(),
+                stress: list.stress(task.id),
+            })
+            .sorted_by(|a, b| b.stress.total_cmp(&a.stress))
+            .collect(),
+    }
+}
+
+pub fn parse_date(date: &str) -> ApiResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| ApiError::BadRequest(format!("Invalid date \"{}\"", date)))
+}
+
+pub fn parse_weekday(weekday: &str) -> ApiResult<Weekday> {
+    weekday
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("Invalid weekday \"{}\"", weekday)))
+}
+
+pub fn get_itinerary(list: &List, date: NaiveDate) -> ItineraryView {
+    let source = if list.schedule.get_scheduled_itinerary(date).is_some() {
+        "scheduled"
+    } else {
+        "default"
+    };
+    ItineraryView {
+        source,
+        timeblocks: blocks(list.schedule.get_itinerary(date)),
+    }
+}
+
+pub fn set_itinerary(
+    list: &mut List,
+    date: NaiveDate,
+    timeblocks: Vec<TimeBlockRecord>,
+) -> ApiResult<ItineraryView> {
+    let itinerary =
+        itinerary(&timeblocks).map_err(|error| ApiError::BadRequest(error.to_string()))?;
+    list.set_itinerary(date, itinerary);
+    Ok(get_itinerary(list, date))
+}
+
+pub fn clear_itinerary(list: &mut List, date: NaiveDate) -> ItineraryView {
+    list.clear_itinerary(date);
+    get_itinerary(list, date)
+}
+
+pub fn get_default_itinerary(list: &List, weekday: Weekday) -> ItineraryView {
+    ItineraryView {
+        source: "default",
+        timeblocks: blocks(list.schedule.get_default_itinerary(weekday)),
+    }
+}
+
+pub fn set_default_itinerary(
+    list: &mut List,
+    weekday: Weekday,
+    timeblocks: Vec<TimeBlockRecord>,
+) -> ApiResult<ItineraryView> {
+    let itinerary =
+        itinerary(&timeblocks).map_err(|error| ApiError::BadRequest(error.to_string()))?;
+    list.set_default_itinerary(weekday, itinerary);
+    Ok(get_default_itinerary(list, weekday))
+}
@@ -0,0 +1,226 @@
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use super::{ApiError, ApiResult, Session, TaskPatch};
+use crate::list::List;
+
+// How often to look for changes to the data file while idle
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const NOT_FOUND: i64 = -32001;
+const CONFLICT: i64 = -32002;
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    jsonrpc: String,
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct TaskParams {
+    id: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateParams {
+    id: usize,
+    #[serde(flatten)]
+    patch: TaskPatch,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkParams {
+    id: usize,
+    subtask: usize,
+}
+
+// Either a task, or a number of hours starting no earlier than start
+#[derive(Debug, Deserialize)]
+struct EarliestParams {
+    id: Option<usize>,
+    hours: Option<f32>,
+    start: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+struct Earliest {
+    earliest_completion: Option<NaiveDateTime>,
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> ApiResult<T> {
+    serde_json::from_value(params).map_err(|error| ApiError::BadRequest(error.to_string()))
+}
+
+fn value<T: Serialize>(value: T) -> ApiResult<Value> {
+    Ok(serde_json::to_value(value).unwrap())
+}
+
+fn earliest_complete(list: &List, params: EarliestParams) -> ApiResult<Earliest> {
+    let (time, start) = match (params.id, params.hours) {
+        (Some(id), None) => {
+            let task = super::get(list, id)?;
+            (list.effective_time(id), params.start.or(task.start))
+        }
+        (None, Some(hours)) => (super::hours(hours)?, params.start),
+        _ => {
+            return Err(ApiError::BadRequest(String::from(
+                "Expected exactly one of id or hours",
+            )));
+        }
+    };
+    Ok(Earliest {
+        earliest_completion: list.schedule.earliest_complete(time, start),
+    })
+}
+
+// The result, and whether the list needs saving afterwards
+fn call(list: &mut List, method: &str, request: Value) -> Option<(ApiResult<Value>, bool)> {
+    let result = match method {
+        "tasks.list" => (value(super::tasks(list)), false),
+        "tasks.get" => (
+            params(request).and_then(|p: TaskParams| value(super::task(list, p.id)?)),
+            false,
+        ),
+        "tasks.create" => (
+            params(request).and_then(|p| value(super::create_task(list, p)?)),
+            true,
+        ),
+        "tasks.update" => (
+            params(request)
+                .and_then(|p: UpdateParams| value(super::update_task(list, p.id, p.patch)?)),
+            true,
+        ),
+        "tasks.delete" => (
+            params(request).and_then(|p: TaskParams| value(super::delete_task(list, p.id)?)),
+            true,
+        ),
+        "tasks.complete" => (
+            params(request).and_then(|p: TaskParams| value(super::complete_task(list, p.id)?)),
+            true,
+        ),
+        "tasks.link" => (
+            params(request).and_then(|p: LinkParams| value(super::link(list, p.id, p.subtask)?)),
+            true,
+        ),
+        "tasks.unlink" => (
+            params(request).and_then(|p: LinkParams| value(super::unlink(list, p.id, p.subtask)?)),
+            true,
+        ),
+        "stress" => (value(super::stress(list)), false),
+        "schedule.earliestComplete" => (
+            params(request).and_then(|p| value(earliest_complete(list, p)?)),
+            false,
+        ),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message},
+    })
+}
+
+fn handle(session: &mut Session, line: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return Some(error(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    let request: Request = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => return Some(error(Value::Null, INVALID_REQUEST, &e.to_string())),
+    };
+    let id = request.id.clone().unwrap_or(Value::Null);
+    if request.jsonrpc != "2.0" {
+        return Some(error(id, INVALID_REQUEST, "Only JSON-RPC 2.0 is supported"));
+    }
+
+    session.begin();
+    let response = match call(&mut session.list, &request.method, request.params) {
+        None => error(
+            id,
+            METHOD_NOT_FOUND,
+            &format!("No method \"{}\"", request.method),
+        ),
+        Some((result, mutates)) => {
+            match result.and_then(|result| {
+                if mutates {
+                    session.save()?;
+                }
+                Ok(result)
+            }) {
+                Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                Err(e) => {
+                    session.revert();
+                    let code = match e {
+                        ApiError::BadRequest(_) => INVALID_PARAMS,
+                        ApiError::NotFound(_) => NOT_FOUND,
+                        ApiError::Conflict(_) => CONFLICT,
+                        ApiError::Storage(_) => INTERNAL_ERROR,
+                    };
+                    error(id, code, &e.to_string())
+                }
+            }
+        }
+    };
+    // Notifications don't get a response
+    request.id.map(|_| response)
+}
+
+fn send(value: &Value) {
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{}", value).unwrap();
+    stdout.flush().unwrap();
+}
+
+pub fn serve(mut session: Session) -> Result<()> {
+    // Requests come one per line, read on another thread so the data file can
+    // be watched in between
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let line = match receiver.recv_timeout(POLL_INTERVAL) {
+            Ok(line) => Some(line?),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        if session.refresh() {
+            send(&json!({"jsonrpc": "2.0", "method": "dataChanged", "params": {}}));
+        }
+        if let Some(line) = line {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = handle(&mut session, &line) {
+                send(&response);
+            }
+        }
+    }
+    Ok(())
+}
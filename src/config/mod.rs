@@ -0,0 +1,83 @@
+use anyhow::Result;
+use dirs::config_dir;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::alerts::Alerts;
+use crate::hooks::Hooks;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    #[default]
+    Yaml,
+    Json,
+    Journal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // Which backend persists the list
+    pub storage: StorageKind,
+    // Where the data file lives, defaults to the config directory
+    pub data_dir: Option<PathBuf>,
+    // Number of old data files kept around by the file backends
+    pub backups: usize,
+    pub hooks: Hooks,
+    // Stress thresholds for the startup warning and `todo check`
+    pub alerts: Alerts,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            storage: StorageKind::default(),
+            data_dir: None,
+            backups: 10,
+            hooks: Hooks::default(),
+            alerts: Alerts::default(),
+        }
+    }
+}
+
+pub fn get_config_dir() -> PathBuf {
+    let mut dir = config_dir().unwrap();
+    dir.push("todo");
+    dir
+}
+
+fn get_config_file() -> PathBuf {
+    let mut file = get_config_dir();
+    file.push("config.yaml");
+    file
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        let mut file = if let Ok(file) = File::open(get_config_file()) {
+            file
+        } else {
+            return Ok(Config::default());
+        };
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        Ok(serde_yaml::from_str(&contents)?)
+    }
+
+    pub fn data_dir(&self) -> PathBuf {
+        self.data_dir.clone().unwrap_or_else(get_config_dir)
+    }
+
+    pub fn data_file(&self) -> PathBuf {
+        let mut file = self.data_dir();
+        file.push(match self.storage {
+            StorageKind::Yaml => "data.yaml",
+            StorageKind::Json => "data.json",
+            StorageKind::Journal => "data.journal",
+        });
+        file
+    }
+}
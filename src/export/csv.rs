@@ -0,0 +1,115 @@
+use chrono::NaiveDateTime;
+use clap::ValueEnum;
+use itertools::Itertools;
+use std::collections::HashSet;
+
+use crate::list::List;
+use crate::query::Query;
+use crate::task::Task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Column {
+    Id,
+    Name,
+    Description,
+    Estimated,
+    Effective,
+    Stress,
+    Start,
+    Deadline,
+    Earliest,
+    Parents,
+    Children,
+    Tags,
+    Priority,
+    Status,
+}
+
+impl Column {
+    fn header(&self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Name => "name",
+            Column::Description => "description",
+            Column::Estimated => "estimated_hours",
+            Column::Effective => "effective_hours",
+            Column::Stress => "stress",
+            Column::Start => "start",
+            Column::Deadline => "deadline",
+            Column::Earliest => "earliest_completion",
+            Column::Parents => "parent_ids",
+            Column::Children => "child_ids",
+            Column::Tags => "tags",
+            Column::Priority => "priority",
+            Column::Status => "status",
+        }
+    }
+
+    fn value(&self, list: &List, task: &Task) -> String {
+        let datetime = |datetime: Option<NaiveDateTime>| {
+            datetime
+                .map(|datetime| datetime.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default()
+        };
+        let ids = |ids: &HashSet<usize>| ids.iter().sorted().join(";");
+        match self {
+            Column::Id => task.id.to_string(),
+            Column::Name => task.name.clone(),
+            Column::Description => task.description.clone(),
+            Column::Estimated => format!("{:.2}", task.estimated_time.as_seconds_f32() / 3600.0),
+            Column::Effective => format!(
+                "{:.2}",
+                list.effective_time(task.id).as_seconds_f32() / 3600.0
+            ),
+            Column::Stress => format!("{:.3}", list.stress(task.id)),
+            Column::Start => datetime(task.start),
+            Column::Deadline => datetime(task.deadline),
+            Column::Earliest => datetime(
+                list.schedule
+                    .earliest_complete(list.effective_time(task.id), task.start),
+            ),
+            Column::Parents => ids(&task.supertasks),
+            Column::Children => ids(&task.subtasks),
+            Column::Tags => task.tags.iter().join(";"),
+            Column::Priority => task.priority.to_string().to_lowercase(),
+            Column::Status => serde_json::to_value(task.status)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_string(),
+        }
+    }
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    field.to_string()
+}
+
+// One row per task with a header row, ids within a cell are separated by ;
+pub fn export(list: &List, columns: &[Column], query: &Query) -> String {
+    let columns = if columns.is_empty() {
+        Column::value_variants()
+    } else {
+        columns
+    };
+    let mut csv = columns.iter().map(|column| column.header()).join(",");
+    csv.push('\n');
+    for task in list
+        .tasks
+        .values()
+        .filter(|task| query.matches(list, task.id))
+        .sorted_by_key(|task| task.id)
+    {
+        csv.push_str(
+            &columns
+                .iter()
+                .map(|column| escape(&column.value(list, task)))
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
@@ -0,0 +1,84 @@
+use anyhow::{Result, anyhow};
+use itertools::Itertools;
+use std::collections::HashSet;
+
+use crate::list::List;
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// Green for the calmest task through to red for the most stressful one
+fn color(stress: f32, max_stress: f32) -> String {
+    let share = if max_stress > 0.0 {
+        (stress / max_stress).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    format!("{:.3} 0.55 1.000", (1.0 - share) / 3.0)
+}
+
+// Nodes are labeled with the estimate and deadline and filled by stress, tasks
+// that can't be done by their deadline get a thick red border.
+pub fn export(list: &List, subtree: Option<usize>) -> Result<String> {
+    let ids: HashSet<usize> = if let Some(subtree) = subtree {
+        if !list.tasks.contains_key(&subtree) {
+            return Err(anyhow!("No task with id {}", subtree));
+        }
+        list.get_all_children(subtree)
+    } else {
+        list.tasks.keys().cloned().collect()
+    };
+    let stresses: Vec<(usize, f32)> = ids
+        .iter()
+        .sorted()
+        .map(|&id| (id, list.stress(id)))
+        .collect();
+    let max_stress = stresses
+        .iter()
+        .map(|(_, stress)| *stress)
+        .fold(0.0, f32::max);
+
+    let mut dot = String::from("digraph todo {\n");
+    dot.push_str("  rankdir=LR;\n");
+    dot.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"sans-serif\"];\n");
+    for &(id, stress) in stresses.iter() {
+        let task = list.tasks.get(&id).unwrap();
+        let effective_time = list.effective_time(id);
+        let mut label = format!(
+            "{}\\nstress {:.2}\\nest {:.1}h",
+            escape(&task.name),
+            stress,
+            effective_time.as_seconds_f32() / 3600.0
+        );
+        let mut late = false;
+        if let Some(deadline) = task.deadline {
+            label.push_str(&format!("\\ndue {}", deadline.format("%Y-%m-%d %H:%M")));
+            late = list
+                .schedule
+                .earliest_complete(effective_time, task.start)
+                .is_none_or(|earliest| earliest > deadline);
+        }
+        let border = if late {
+            ", color=\"red\", penwidth=3"
+        } else {
+            ""
+        };
+        dot.push_str(&format!(
+            "  t{} [label=\"{}\", fillcolor=\"{}\"{}];\n",
+            id,
+            label,
+            color(stress, max_stress),
+            border
+        ));
+    }
+    for &(id, _) in stresses.iter() {
+        for subtask in list.tasks.get(&id).unwrap().subtasks.iter().sorted() {
+            if ids.contains(subtask) {
+                dot.push_str(&format!("  t{} -> t{};\n", id, subtask));
+            }
+        }
+    }
+    dot.push_str("}\n");
+    Ok(dot)
+}
@@ -0,0 +1,231 @@
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, TimeDelta, Timelike, Utc, Weekday};
+use itertools::Itertools;
+use std::collections::HashMap;
+
+use crate::list::List;
+use crate::query::Query;
+use crate::schedule::{Itinerary, WEEK};
+use crate::task::{Priority, Status};
+
+const PRODID: &str = "-//todo//todo//EN";
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Lines longer than 75 bytes continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+// Times are floating, the calendar shows them in whatever zone it is in
+fn datetime(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn duration(time: TimeDelta) -> String {
+    format!("PT{}M", time.num_minutes())
+}
+
+fn byday(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+struct Calendar {
+    lines: Vec<String>,
+    stamp: String,
+}
+
+impl Calendar {
+    fn push(&mut self, name: &str, value: impl AsRef<str>) {
+        self.lines.push(format!("{}:{}", name, value.as_ref()));
+    }
+
+    // A weekly itinerary repeats from date on, except on the skipped dates
+    fn timeblocks(
+        &mut self,
+        uid: &str,
+        date: NaiveDate,
+        itinerary: &Itinerary,
+        weekly_except: Option<&[NaiveDate]>,
+    ) {
+        for block in itinerary
+            .timeblocks
+            .iter()
+            .sorted_by_key(|block| block.start)
+        {
+            self.push("BEGIN", "VEVENT");
+            self.push(
+                "UID",
+                format!("{}-{}@todo", uid, block.start.format("%H%M%S")),
+            );
+            self.push("DTSTAMP", self.stamp.clone());
+            self.push("SUMMARY", "Work time");
+            self.push("DTSTART", datetime(date.and_time(block.start)));
+            self.push("DTEND", datetime(date.and_time(block.end)));
+            self.push("TRANSP", "TRANSPARENT");
+            if let Some(skipped) = weekly_except {
+                self.push(
+                    "RRULE",
+                    format!("FREQ=WEEKLY;BYDAY={}", byday(date.weekday())),
+                );
+                for skip in skipped {
+                    self.push("EXDATE", datetime(skip.and_time(block.start)));
+                }
+            }
+            self.push("END", "VEVENT");
+        }
+    }
+}
+
+// Tasks become VTODOs, timeblocks and the work planned into them become
+// VEVENTs. The default schedule repeats weekly and skips the dates that have
+// their own itinerary. Work is planned most stressful task first.
+pub fn export(list: &List, query: &Query) -> String {
+    let mut calendar = Calendar {
+        lines: Vec::new(),
+        stamp: Utc::now().format("%Y%m%dT%H%M%SZ").to_string(),
+    };
+    calendar.push("BEGIN", "VCALENDAR");
+    calendar.push("VERSION", "2.0");
+    calendar.push("PRODID", PRODID);
+
+    for task in list
+        .tasks
+        .values()
+        .filter(|task| query.matches(list, task.id))
+        .sorted_by_key(|task| task.id)
+    {
+        calendar.push("BEGIN", "VTODO");
+        calendar.push("UID", format!("{}@todo", task.uid));
+        calendar.push("DTSTAMP", calendar.stamp.clone());
+        calendar.push("SUMMARY", escape(&task.name));
+        let mut description = task.description.clone();
+        if !description.is_empty() {
+            description.push('\n');
+        }
+        description.push_str(&format!(
+            "Estimated Hours: {}",
+            task.estimated_time.num_minutes() as f32 / 60.0
+        ));
+        calendar.push("DESCRIPTION", escape(&description));
+        if let Some(start) = task.start {
+            calendar.push("DTSTART", datetime(start));
+        }
+        if let Some(deadline) = task.deadline {
+            calendar.push("DUE", datetime(deadline));
+        }
+        // 1 is the highest, 5 the middle and 9 the lowest
+        let priority = match task.priority {
+            Priority::Urgent => 1,
+            Priority::High => 3,
+            Priority::Normal => 5,
+            Priority::Low => 9,
+        };
+        calendar.push("PRIORITY", priority.to_string());
+        // Closed tasks never make it into the list, so only these two apply
+        let status = match task.status {
+            Status::InProgress => "IN-PROCESS",
+            _ => "NEEDS-ACTION",
+        };
+        calendar.push("STATUS", status);
+        if !task.tags.is_empty() {
+            calendar.push(
+                "CATEGORIES",
+                task.tags.iter().map(|tag| escape(tag)).join(","),
+            );
+        }
+        calendar.push("X-ESTIMATED-DURATION", duration(task.estimated_time));
+        for supertask in task
+            .supertasks
+            .iter()
+            .filter(|&&supertask| query.matches(list, supertask))
+            .sorted()
+        {
+            let parent = list.tasks.get(supertask).unwrap();
+            calendar.push("RELATED-TO;RELTYPE=PARENT", format!("{}@todo", parent.uid));
+        }
+        calendar.push("END", "VTODO");
+    }
+
+    let scheduled = list.schedule.scheduled_itineraries();
+    for (date, itinerary) in scheduled.iter() {
+        let uid = format!("schedule-{}", date.format("%Y%m%d"));
+        calendar.timeblocks(&uid, *date, itinerary, None);
+    }
+
+    let today = Local::now().date_naive();
+    for weekday in WEEK {
+        let first = today
+            .iter_days()
+            .find(|date| date.weekday() == weekday)
+            .unwrap();
+        let skipped: Vec<NaiveDate> = scheduled
+            .iter()
+            .map(|(date, _)| *date)
+            .filter(|date| date.weekday() == weekday && *date >= first)
+            .collect();
+        calendar.timeblocks(
+            &format!("default-{}", byday(weekday)),
+            first,
+            list.schedule.get_default_itinerary(weekday),
+            Some(&skipped),
+        );
+    }
+
+    // From the next whole minute, so the slots don't land on odd seconds
+    let now = Local::now().naive_local();
+    let now = now.with_second(0).unwrap().with_nanosecond(0).unwrap() + TimeDelta::minutes(1);
+    let work: Vec<_> = list
+        .tasks
+        .values()
+        .map(|task| (task, list.stress(task.id)))
+        .sorted_by(|(a, a_stress), (b, b_stress)| {
+            b_stress.total_cmp(a_stress).then(a.id.cmp(&b.id))
+        })
+        .map(|(task, _)| (task.id, task.start, task.estimated_time))
+        .collect();
+    // Numbered per task, so exporting again replaces the same events
+    let mut count: HashMap<usize, usize> = HashMap::new();
+    for slot in list.schedule.plan(now, &work) {
+        if !query.matches(list, slot.id) {
+            continue;
+        }
+        let task = list.tasks.get(&slot.id).unwrap();
+        let number = count.entry(slot.id).or_default();
+        *number += 1;
+        calendar.push("BEGIN", "VEVENT");
+        calendar.push("UID", format!("plan-{}-{}@todo", task.uid, number));
+        calendar.push("DTSTAMP", calendar.stamp.clone());
+        calendar.push("SUMMARY", escape(&task.name));
+        calendar.push("DTSTART", datetime(slot.start));
+        calendar.push("DTEND", datetime(slot.end));
+        calendar.push("RELATED-TO", format!("{}@todo", task.uid));
+        calendar.push("END", "VEVENT");
+    }
+
+    calendar.push("END", "VCALENDAR");
+    calendar.lines.iter().map(|line| fold(line)).collect()
+}
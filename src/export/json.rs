@@ -0,0 +1,13 @@
+use anyhow::Result;
+
+use super::schema::Document;
+use crate::list::List;
+use crate::query::Query;
+
+pub fn export(list: &List, query: &Query) -> Result<String> {
+    let mut document = Document::from_list(list);
+    document.retain(|id| query.matches(list, id));
+    let mut json = serde_json::to_string_pretty(&document)?;
+    json.push('\n');
+    Ok(json)
+}
@@ -0,0 +1,19 @@
+pub mod csv;
+pub mod dot;
+pub mod ics;
+pub mod json;
+pub mod schema;
+use anyhow::Result;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+// Writes to the file if one was given, otherwise stdout
+pub fn write_output(output: Option<&Path>, contents: &str) -> Result<()> {
+    if let Some(output) = output {
+        File::create(output)?.write_all(contents.as_bytes())?;
+        return Ok(());
+    }
+    std::io::stdout().write_all(contents.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,293 @@
+//! The interchange format for a whole list. Field names and meanings are
+//! stable within a version, new optional fields may be added but anything
+//! that changes how an existing field reads bumps `version`.
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "tasks": [{
+//!     "id": 3,                          // short number, unique in this file
+//!     "uid": "0b6e...",                 // stable across lists
+//!     "name": "Write report",
+//!     "description": "",
+//!     "estimated_hours": 4.0,
+//!     "estimated_stress": 1.5,          // optional
+//!     "start": "2025-05-01T09:00:00",   // optional, local time
+//!     "deadline": "2025-05-03T23:59:59",// optional, local time
+//!     "subtasks": [4, 5],               // ids of tasks in this file
+//!     "supertasks": []
+//!   }],
+//!   "schedule": { "2025-05-02": [{ "start": "13:00:00", "end": "17:00:00" }] },
+//!   "default_schedule": { "monday": [{ "start": "09:00:00", "end": "17:00:00" }], ... },
+//!   "views": { "Soon": { "filter": "due<7d", "sort": "deadline" } }
+//! }
+//! ```
+use anyhow::{Result, anyhow};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::list::List;
+use crate::query::view::SavedView;
+use crate::schedule::{Itinerary, Schedule, TimeBlock, WEEK};
+use crate::task::{Priority, Status, StatusChange, Task};
+
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Document {
+    pub version: u32,
+    pub tasks: Vec<TaskRecord>,
+    #[serde(default)]
+    pub schedule: BTreeMap<NaiveDate, Vec<TimeBlockRecord>>,
+    #[serde(default)]
+    pub default_schedule: WeekRecord,
+    #[serde(default)]
+    pub views: BTreeMap<String, SavedView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub id: usize,
+    #[serde(default)]
+    pub uid: Option<Uuid>,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub estimated_hours: f32,
+    #[serde(default)]
+    pub estimated_stress: Option<f32>,
+    #[serde(default)]
+    pub start: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub deadline: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub subtasks: Vec<usize>,
+    #[serde(default)]
+    pub supertasks: Vec<usize>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub status: Status,
+    #[serde(default)]
+    pub status_history: Vec<StatusChange>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TimeBlockRecord {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeekRecord {
+    pub monday: Vec<TimeBlockRecord>,
+    pub tuesday: Vec<TimeBlockRecord>,
+    pub wednesday: Vec<TimeBlockRecord>,
+    pub thursday: Vec<TimeBlockRecord>,
+    pub friday: Vec<TimeBlockRecord>,
+    pub saturday: Vec<TimeBlockRecord>,
+    pub sunday: Vec<TimeBlockRecord>,
+}
+
+impl WeekRecord {
+    fn day(&mut self, weekday: Weekday) -> &mut Vec<TimeBlockRecord> {
+        match weekday {
+            Weekday::Mon => &mut self.monday,
+            Weekday::Tue => &mut self.tuesday,
+            Weekday::Wed => &mut self.wednesday,
+            Weekday::Thu => &mut self.thursday,
+            Weekday::Fri => &mut self.friday,
+            Weekday::Sat => &mut self.saturday,
+            Weekday::Sun => &mut self.sunday,
+        }
+    }
+}
+
+impl From<&Task> for TaskRecord {
+    fn from(task: &Task) -> Self {
+        let mut subtasks: Vec<usize> = task.subtasks.iter().cloned().collect();
+        subtasks.sort();
+        let mut supertasks: Vec<usize> = task.supertasks.iter().cloned().collect();
+        supertasks.sort();
+        TaskRecord {
+            id: task.id,
+            uid: Some(task.uid),
+            name: task.name.clone(),
+            description: task.description.clone(),
+            estimated_hours: task.estimated_time.as_seconds_f32() / 3600.0,
+            estimated_stress: task.estimated_stress,
+            start: task.start,
+            deadline: task.deadline,
+            subtasks,
+            supertasks,
+            tags: task.tags.iter().cloned().collect(),
+            priority: task.priority,
+            status: task.status,
+            status_history: task.status_history.clone(),
+        }
+    }
+}
+
+impl TaskRecord {
+    pub fn to_task(&self) -> Task {
+        let mut task = Task::new(
+            self.name.clone(),
+            self.description.clone(),
+            TimeDelta::seconds((self.estimated_hours * 3600.0) as i64),
+            self.estimated_stress,
+            self.start,
+            self.deadline,
+        );
+        task.id = self.id;
+        if let Some(uid) = self.uid {
+            task.uid = uid;
+        }
+        task.subtasks = self.subtasks.iter().cloned().collect();
+        task.supertasks = self.supertasks.iter().cloned().collect();
+        task.set_tags(&self.tags);
+        task.priority = self.priority;
+        task.status = self.status;
+        task.status_history = self.status_history.clone();
+        task
+    }
+}
+
+fn reaches(tasks: &HashMap<usize, Task>, from: usize, to: usize) -> bool {
+    let mut seen = HashSet::from([from]);
+    let mut stack = vec![from];
+    while let Some(id) = stack.pop() {
+        if id == to {
+            return true;
+        }
+        for &subtask in tasks.get(&id).unwrap().subtasks.iter() {
+            if seen.insert(subtask) {
+                stack.push(subtask);
+            }
+        }
+    }
+    false
+}
+
+pub fn blocks(itinerary: &Itinerary) -> Vec<TimeBlockRecord> {
+    let mut blocks: Vec<TimeBlockRecord> = itinerary
+        .timeblocks
+        .iter()
+        .map(|block| TimeBlockRecord {
+            start: block.start,
+            end: block.end,
+        })
+        .collect();
+    blocks.sort_by_key(|block| block.start);
+    blocks
+}
+
+pub fn itinerary(blocks: &[TimeBlockRecord]) -> Result<Itinerary> {
+    let mut itinerary = Itinerary::new();
+    for block in blocks {
+        itinerary.add_timeblock(TimeBlock::from_start_end(block.start, block.end))?;
+    }
+    Ok(itinerary)
+}
+
+impl Document {
+    pub fn from_list(list: &List) -> Self {
+        let mut tasks: Vec<TaskRecord> = list.tasks.values().map(TaskRecord::from).collect();
+        tasks.sort_by_key(|task| task.id);
+        let mut default_schedule = WeekRecord::default();
+        for weekday in WEEK {
+            *default_schedule.day(weekday) = blocks(list.schedule.get_default_itinerary(weekday));
+        }
+        Document {
+            version: VERSION,
+            tasks,
+            schedule: list
+                .schedule
+                .scheduled_itineraries()
+                .into_iter()
+                .map(|(date, itinerary)| (date, blocks(itinerary)))
+                .collect(),
+            default_schedule,
+            views: list.views.clone(),
+        }
+    }
+
+    // Drops the other tasks along with any links to them
+    pub fn retain<F: Fn(usize) -> bool>(&mut self, keep: F) {
+        self.tasks.retain(|task| keep(task.id));
+        for task in self.tasks.iter_mut() {
+            task.subtasks.retain(|&id| keep(id));
+            task.supertasks.retain(|&id| keep(id));
+        }
+    }
+
+    pub fn to_list(&self) -> Result<List> {
+        if self.version > VERSION {
+            return Err(anyhow!(
+                "Schema version {} is newer than the supported version {}",
+                self.version,
+                VERSION
+            ));
+        }
+        let mut tasks: HashMap<usize, Task> = HashMap::new();
+        for record in self.tasks.iter() {
+            if tasks.insert(record.id, record.to_task()).is_some() {
+                return Err(anyhow!("Task id {} is used more than once", record.id));
+            }
+        }
+        // Links are symmetric in the list, either side of a link is enough here
+        let links: HashSet<(usize, usize)> = self
+            .tasks
+            .iter()
+            .flat_map(|record| {
+                let id = record.id;
+                record
+                    .subtasks
+                    .iter()
+                    .map(move |&subtask| (id, subtask))
+                    .chain(
+                        record
+                            .supertasks
+                            .iter()
+                            .map(move |&supertask| (supertask, id)),
+                    )
+            })
+            .collect();
+        for task in tasks.values_mut() {
+            task.subtasks.clear();
+            task.supertasks.clear();
+        }
+        for (id, subtask) in links.into_iter().sorted() {
+            if !tasks.contains_key(&id) || !tasks.contains_key(&subtask) {
+                return Err(anyhow!(
+                    "Link {} -> {} refers to a missing task",
+                    id,
+                    subtask
+                ));
+            }
+            if reaches(&tasks, subtask, id) {
+                return Err(anyhow!("Link {} -> {} forms a cycle", id, subtask));
+            }
+            tasks.get_mut(&id).unwrap().subtasks.insert(subtask);
+            tasks.get_mut(&subtask).unwrap().supertasks.insert(id);
+        }
+
+        let mut schedule = Schedule::new();
+        for (&date, blocks) in self.schedule.iter() {
+            schedule.set_itinerary(date, itinerary(blocks)?);
+        }
+        let mut default_schedule = self.default_schedule.clone();
+        for weekday in WEEK {
+            schedule.set_default_itinerary(weekday, itinerary(default_schedule.day(weekday))?);
+        }
+        let mut list = List::from_parts(tasks, schedule);
+        list.views = self.views.clone();
+        list.migrate();
+        Ok(list)
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::list::List;
+use crate::task::Task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    Add,
+    Modify,
+    Complete,
+    StressThreshold,
+}
+
+impl Hook {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::Add => "on-add",
+            Hook::Modify => "on-modify",
+            Hook::Complete => "on-complete",
+            Hook::StressThreshold => "on-stress-threshold",
+        }
+    }
+}
+
+// Shell commands run when tasks change, each gets the task as JSON on stdin
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Hooks {
+    pub on_add: Option<String>,
+    pub on_modify: Option<String>,
+    pub on_complete: Option<String>,
+    pub on_stress_threshold: Option<String>,
+    // A task's stress has to reach this to trigger on-stress-threshold,
+    // defaults to the per-task alert threshold
+    pub stress_threshold: Option<f32>,
+}
+
+impl Hooks {
+    fn command(&self, hook: Hook) -> Option<&String> {
+        match hook {
+            Hook::Add => self.on_add.as_ref(),
+            Hook::Modify => self.on_modify.as_ref(),
+            Hook::Complete => self.on_complete.as_ref(),
+            Hook::StressThreshold => self.on_stress_threshold.as_ref(),
+        }
+    }
+
+    pub fn run(&self, hook: Hook, task: &Task) {
+        let command = if let Some(command) = self.command(hook) {
+            command
+        } else {
+            return;
+        };
+        // A broken hook shouldn't stop the change it was told about
+        if let Err(error) = execute(hook, command, task) {
+            eprintln!("Hook {} failed: {}", hook.name(), error);
+        }
+    }
+}
+
+fn execute(hook: Hook, command: &str, task: &Task) -> anyhow::Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("TODO_HOOK", hook.name())
+        .stdin(Stdio::piped())
+        // Keeps stdout clean for the rpc mode
+        .stdout(std::io::stderr())
+        .spawn()?;
+    let mut stdin = child.stdin.take().unwrap();
+    serde_json::to_writer(&mut stdin, task)?;
+    writeln!(stdin)?;
+    drop(stdin);
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(anyhow::anyhow!("exited with {}", status));
+    }
+    Ok(())
+}
+
+impl List {
+    pub fn run_hook(&self, hook: Hook, id: usize) {
+        self.hooks.run(hook, self.tasks.get(&id).unwrap());
+    }
+
+    // Tasks whose stress is at or above the configured threshold
+    pub fn stressed_tasks(&self) -> HashSet<usize> {
+        let threshold = if let Some(threshold) = self.hooks.stress_threshold {
+            threshold
+        } else {
+            return HashSet::new();
+        };
+        self.tasks
+            .keys()
+            .cloned()
+            .filter(|&id| self.stress(id) >= threshold)
+            .collect()
+    }
+
+    // Runs on-stress-threshold for tasks that weren't over it before, returns
+    // the tasks over it now
+    pub fn run_stress_hooks(&self, before: &HashSet<usize>) -> HashSet<usize> {
+        let stressed = self.stressed_tasks();
+        for &id in stressed.difference(before) {
+            self.run_hook(Hook::StressThreshold, id);
+        }
+        stressed
+    }
+}
@@ -0,0 +1,271 @@
+use anyhow::{Result, anyhow};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use std::collections::HashSet;
+
+use super::parse_duration;
+
+#[derive(Debug, Clone)]
+pub struct Busy {
+    pub summary: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// Continuation lines start with a space or tab
+fn unfold(contents: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim_end_matches('\r');
+        if let (Some(rest), Some(last)) = (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            last.push_str(rest);
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property> {
+    let mut quoted = false;
+    let split = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ':' && !quoted
+    })?;
+    let (head, value) = (&line[..split.0], &line[split.0 + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_uppercase();
+    let params = parts
+        .filter_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            Some((key.to_uppercase(), value.trim_matches('"').to_string()))
+        })
+        .collect();
+    Some(Property {
+        name,
+        params,
+        value: value.to_string(),
+    })
+}
+
+// UTC times are converted to local time. Times with a TZID are read as local
+// since there is no timezone database to look them up in.
+fn parse_datetime(property: &Property) -> Result<(NaiveDateTime, bool)> {
+    let value = property.value.trim();
+    if property.param("VALUE") == Some("DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map_err(|_| anyhow!("Invalid date \"{}\"", value))?;
+        return Ok((date.and_time(NaiveTime::MIN), true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let utc = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .map_err(|_| anyhow!("Invalid date \"{}\"", value))?;
+        return Ok((utc.and_utc().with_timezone(&Local).naive_local(), false));
+    }
+    let datetime = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .map_err(|_| anyhow!("Invalid date \"{}\"", value))?;
+    Ok((datetime, false))
+}
+
+fn parse_weekday(day: &str) -> Option<Weekday> {
+    // Ordinals like 1MO only make sense for monthly rules
+    match day.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+#[derive(Default)]
+struct Event {
+    summary: String,
+    start: Option<(NaiveDateTime, bool)>,
+    end: Option<NaiveDateTime>,
+    duration: Option<TimeDelta>,
+    rrule: Option<String>,
+    exdates: HashSet<NaiveDateTime>,
+    free: bool,
+}
+
+impl Event {
+    // Start times of every occurrence before the horizon. Only daily and
+    // weekly rules repeat, anything else is reported and used once.
+    fn occurrences(
+        &self,
+        start: NaiveDateTime,
+        horizon: NaiveDateTime,
+        warnings: &mut Vec<String>,
+    ) -> Vec<NaiveDateTime> {
+        let Some(rrule) = self.rrule.as_ref() else {
+            return vec![start];
+        };
+        let mut frequency = "";
+        let mut interval = 1;
+        let mut count = None;
+        let mut until = None;
+        let mut weekdays: Vec<Weekday> = Vec::new();
+        for part in rrule.split(';') {
+            let Some((key, value)) = part.split_once('=') else {
+                continue;
+            };
+            match key {
+                "FREQ" => frequency = value,
+                "INTERVAL" => match value.parse() {
+                    Ok(value) if value >= 1 => interval = value,
+                    _ => warnings.push(format!(
+                        "\"{}\" has an invalid interval {}, 1 was used instead",
+                        self.summary, value
+                    )),
+                },
+                "COUNT" => count = value.parse::<usize>().ok(),
+                "UNTIL" => {
+                    let property = Property {
+                        name: String::from("UNTIL"),
+                        params: Vec::new(),
+                        value: value.to_string(),
+                    };
+                    // An all day until still includes that day
+                    until = parse_datetime(&property).ok().map(|(until, all_day)| {
+                        if all_day {
+                            until + TimeDelta::days(1)
+                        } else {
+                            until
+                        }
+                    });
+                }
+                "BYDAY" => weekdays = value.split(',').filter_map(parse_weekday).collect(),
+                _ => {}
+            }
+        }
+        let step = match frequency {
+            "DAILY" => TimeDelta::days(interval),
+            "WEEKLY" => TimeDelta::weeks(interval),
+            _ => {
+                warnings.push(format!(
+                    "\"{}\" repeats {}, only the first occurrence was used",
+                    self.summary, frequency
+                ));
+                return vec![start];
+            }
+        };
+        if frequency == "DAILY" || weekdays.is_empty() {
+            weekdays = vec![start.weekday()];
+        }
+        let mut occurrences = Vec::new();
+        let week_start =
+            start.date() - TimeDelta::days(start.weekday().num_days_from_monday() as i64);
+        let mut period = week_start.and_time(start.time());
+        if frequency == "DAILY" {
+            period = start;
+        }
+        while period < horizon && until.is_none_or(|until| period <= until) {
+            let mut days: Vec<NaiveDateTime> = if frequency == "DAILY" {
+                vec![period]
+            } else {
+                weekdays
+                    .iter()
+                    .map(|weekday| period + TimeDelta::days(weekday.num_days_from_monday() as i64))
+                    .collect()
+            };
+            days.sort();
+            for day in days {
+                if day < start || until.is_some_and(|until| day > until) {
+                    continue;
+                }
+                if count.is_some_and(|count| occurrences.len() >= count) {
+                    return occurrences;
+                }
+                occurrences.push(day);
+            }
+            period += step;
+        }
+        occurrences
+    }
+}
+
+// Opaque events from now until the horizon, recurring ones expanded. Returns
+// warnings for anything that could only be read partially.
+pub fn busy_times(contents: &str, horizon: NaiveDateTime) -> Result<(Vec<Busy>, Vec<String>)> {
+    let now = Local::now().naive_local();
+    let mut events = Vec::new();
+    let mut event: Option<Event> = None;
+    for line in unfold(contents) {
+        let Some(property) = parse_property(&line) else {
+            continue;
+        };
+        match (property.name.as_str(), event.as_mut()) {
+            ("BEGIN", None) if property.value == "VEVENT" => event = Some(Event::default()),
+            ("END", Some(_)) if property.value == "VEVENT" => events.push(event.take().unwrap()),
+            ("SUMMARY", Some(event)) => event.summary = property.value.replace("\\,", ","),
+            ("DTSTART", Some(event)) => event.start = Some(parse_datetime(&property)?),
+            ("DTEND", Some(event)) => event.end = Some(parse_datetime(&property)?.0),
+            ("DURATION", Some(event)) => event.duration = parse_duration(&property.value),
+            ("RRULE", Some(event)) => event.rrule = Some(property.value.clone()),
+            ("EXDATE", Some(event)) => {
+                for value in property.value.split(',') {
+                    let exdate = Property {
+                        name: property.name.clone(),
+                        params: property.params.clone(),
+                        value: value.to_string(),
+                    };
+                    event.exdates.insert(parse_datetime(&exdate)?.0);
+                }
+            }
+            ("TRANSP", Some(event)) => event.free = property.value == "TRANSPARENT",
+            ("STATUS", Some(event)) => event.free |= property.value == "CANCELLED",
+            _ => {}
+        }
+    }
+
+    let mut busy = Vec::new();
+    let mut warnings = Vec::new();
+    for event in events.iter().filter(|event| !event.free) {
+        let Some((start, all_day)) = event.start else {
+            warnings.push(format!("\"{}\" has no start", event.summary));
+            continue;
+        };
+        let length = if let Some(end) = event.end {
+            end - start
+        } else if let Some(duration) = event.duration {
+            duration
+        } else if all_day {
+            TimeDelta::days(1)
+        } else {
+            TimeDelta::zero()
+        };
+        for occurrence in event.occurrences(start, horizon, &mut warnings) {
+            let end = occurrence + length;
+            if event.exdates.contains(&occurrence) || end <= now || occurrence >= horizon {
+                continue;
+            }
+            busy.push(Busy {
+                summary: event.summary.clone(),
+                start: occurrence.max(now),
+                end,
+            });
+        }
+    }
+    busy.sort_by_key(|busy| busy.start);
+    Ok((busy, warnings))
+}
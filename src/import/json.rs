@@ -0,0 +1,9 @@
+use anyhow::Result;
+
+use crate::export::schema::Document;
+use crate::list::List;
+
+pub fn import(contents: &str) -> Result<List> {
+    let document: Document = serde_json::from_str(contents)?;
+    document.to_list()
+}
@@ -0,0 +1,131 @@
+pub mod ics;
+pub mod json;
+pub mod taskwarrior;
+pub mod todotxt;
+use chrono::TimeDelta;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::list::List;
+use crate::merge::cli::resolve;
+use crate::task::Task;
+
+// Imported tasks matching existing ones by uid are merged field by field,
+// the rest are added with fresh ids where theirs collide.
+pub fn merge_into(list: &mut List, imported: List) {
+    let (merged, report) = List::merge(&List::new(), list, imported, &mut resolve);
+    print!("{}", report);
+    *list = merged;
+}
+
+// Prints the imported hierarchy without touching the current list
+pub fn preview(list: &List) {
+    fn show(list: &List, id: usize, depth: usize) {
+        let task = list.tasks.get(&id).unwrap();
+        let mut line = format!(
+            "{}{} [{}h]",
+            "  ".repeat(depth),
+            task.name,
+            task.estimated_time.num_minutes() as f32 / 60.0
+        );
+        if let Some(start) = task.start {
+            line.push_str(&format!(" start {}", start));
+        }
+        if let Some(deadline) = task.deadline {
+            line.push_str(&format!(" due {}", deadline));
+        }
+        println!("{}", line);
+        let mut subtasks: Vec<&usize> = task.subtasks.iter().collect();
+        subtasks.sort();
+        for &subtask in subtasks {
+            show(list, subtask, depth + 1);
+        }
+    }
+    let mut roots: Vec<&Task> = list
+        .tasks
+        .values()
+        .filter(|task| task.supertasks.is_empty())
+        .collect();
+    roots.sort_by_key(|task| task.id);
+    for root in roots {
+        show(list, root.id, 0);
+    }
+    println!("{} tasks would be imported", list.tasks.len());
+}
+
+// Accepts plain hours ("1.5"), suffixed amounts ("90m", "2h", "1d") and ISO
+// 8601 durations ("PT2H30M")
+pub fn parse_duration(value: &str) -> Option<TimeDelta> {
+    let value = value.trim().to_lowercase();
+    if let Some(iso) = value.strip_prefix('p') {
+        let mut minutes = 0.0;
+        let mut number = String::new();
+        let mut in_time = false;
+        for c in iso.chars() {
+            match c {
+                't' => in_time = true,
+                '0'..='9' | '.' => number.push(c),
+                unit => {
+                    let amount: f32 = number.parse().ok()?;
+                    number.clear();
+                    minutes += amount
+                        * match (unit, in_time) {
+                            ('w', false) => 7.0 * 24.0 * 60.0,
+                            ('d', false) => 24.0 * 60.0,
+                            ('h', true) => 60.0,
+                            ('m', true) => 1.0,
+                            ('s', true) => 1.0 / 60.0,
+                            _ => return None,
+                        };
+                }
+            }
+        }
+        return TimeDelta::try_minutes(minutes.round() as i64);
+    }
+    let (amount, minutes) = if let Some(amount) = value.strip_suffix("min") {
+        (amount, 1.0)
+    } else if let Some(amount) = value.strip_suffix('m') {
+        (amount, 1.0)
+    } else if let Some(amount) = value.strip_suffix('h') {
+        (amount, 60.0)
+    } else if let Some(amount) = value.strip_suffix('d') {
+        (amount, 24.0 * 60.0)
+    } else {
+        (value.as_str(), 60.0)
+    };
+    let amount: f32 = amount.trim().parse().ok()?;
+    TimeDelta::try_minutes((amount * minutes).round() as i64)
+}
+
+// Projects become supertasks, dotted names nest ("Home.Garden")
+pub fn project_task(list: &mut List, projects: &mut HashMap<String, usize>, name: &str) -> usize {
+    let mut parent: Option<usize> = None;
+    let mut path = String::new();
+    for part in name.split('.').filter(|part| !part.is_empty()) {
+        if !path.is_empty() {
+            path.push('.');
+        }
+        path.push_str(part);
+        let id = if let Some(&id) = projects.get(&path) {
+            id
+        } else {
+            let mut task = Task::new(
+                part.to_string(),
+                String::new(),
+                TimeDelta::zero(),
+                None,
+                None,
+                None,
+            );
+            task.uid = Uuid::new_v5(&Uuid::NAMESPACE_OID, format!("project:{}", path).as_bytes());
+            let id = list.insert_task(task);
+            if let Some(parent) = parent {
+                list.add_subtask(parent, id);
+            }
+            projects.insert(path.clone(), id);
+            id
+        };
+        parent = Some(id);
+    }
+    parent.unwrap()
+}
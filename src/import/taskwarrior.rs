@@ -0,0 +1,134 @@
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{parse_duration, project_task};
+use crate::list::List;
+use crate::task::{Priority, Task};
+
+#[derive(Debug, Deserialize)]
+struct Annotation {
+    description: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Export {
+    uuid: Uuid,
+    description: String,
+    #[serde(default)]
+    status: String,
+    due: Option<String>,
+    scheduled: Option<String>,
+    project: Option<String>,
+    priority: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    // An array of uuids, or a comma separated string in older versions
+    #[serde(default)]
+    depends: Value,
+    #[serde(default)]
+    annotations: Vec<Annotation>,
+    #[serde(flatten)]
+    udas: HashMap<String, Value>,
+}
+
+// Taskwarrior stores UTC as 20250105T170000Z
+fn parse_date(value: &str) -> Result<NaiveDateTime> {
+    let utc = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+        .map_err(|_| anyhow!("Invalid date \"{}\"", value))?;
+    Ok(utc.and_utc().with_timezone(&Local).naive_local())
+}
+
+fn priority(priority: &str) -> Priority {
+    match priority {
+        "H" => Priority::High,
+        "L" => Priority::Low,
+        _ => Priority::Normal,
+    }
+}
+
+fn depends(value: &Value) -> Vec<Uuid> {
+    let uuids: Vec<&str> = match value {
+        Value::Array(values) => values.iter().filter_map(Value::as_str).collect(),
+        Value::String(values) => values.split(',').collect(),
+        _ => Vec::new(),
+    };
+    uuids
+        .into_iter()
+        .filter_map(|uuid| Uuid::parse_str(uuid.trim()).ok())
+        .collect()
+}
+
+// Reads `task export` output, either a json array or one object per line.
+// Only pending and waiting tasks come across, depends become subtasks and the
+// estimate is read from the named UDA as hours or a duration.
+pub fn import(contents: &str, estimate_uda: &str) -> Result<List> {
+    let exports: Vec<Export> = if contents.trim_start().starts_with('[') {
+        serde_json::from_str(contents)?
+    } else {
+        contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut list = List::new();
+    let mut projects: HashMap<String, usize> = HashMap::new();
+    let mut ids: HashMap<Uuid, usize> = HashMap::new();
+    let exports: Vec<Export> = exports
+        .into_iter()
+        .filter(|export| export.status == "pending" || export.status == "waiting")
+        .collect();
+    for export in exports.iter() {
+        let estimate = match export.udas.get(estimate_uda) {
+            Some(Value::Number(hours)) => {
+                TimeDelta::try_minutes((hours.as_f64().unwrap_or(0.0) * 60.0).round() as i64)
+            }
+            Some(Value::String(duration)) => parse_duration(duration),
+            _ => Some(TimeDelta::hours(1)),
+        }
+        .ok_or_else(|| anyhow!("Invalid estimate for \"{}\"", export.description))?;
+        let description = export
+            .annotations
+            .iter()
+            .map(|annotation| annotation.description.clone())
+            .collect::<Vec<String>>()
+            .join("\n");
+        let mut task = Task::new(
+            export.description.clone(),
+            description,
+            estimate,
+            None,
+            export.scheduled.as_deref().map(parse_date).transpose()?,
+            export.due.as_deref().map(parse_date).transpose()?,
+        );
+        task.uid = export.uuid;
+        task.set_tags(&export.tags);
+        task.priority = export.priority.as_deref().map(priority).unwrap_or_default();
+        let id = list.insert_task(task);
+        ids.insert(export.uuid, id);
+        if let Some(project) = export.project.as_deref() {
+            let project = project_task(&mut list, &mut projects, project);
+            list.add_subtask(project, id);
+        }
+    }
+
+    for export in exports.iter() {
+        let id = ids[&export.uuid];
+        for dependency in depends(&export.depends) {
+            // Completed dependencies weren't imported
+            let Some(&subtask) = ids.get(&dependency) else {
+                continue;
+            };
+            if list.get_all_children(subtask).contains(&id) {
+                return Err(anyhow!("\"{}\" depends on itself", export.description));
+            }
+            list.add_subtask(id, subtask);
+        }
+    }
+    Ok(list)
+}
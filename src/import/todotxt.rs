@@ -0,0 +1,112 @@
+use anyhow::{Result, anyhow};
+use chrono::{NaiveDate, NaiveTime, TimeDelta};
+use itertools::Itertools;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::{parse_duration, project_task};
+use crate::list::List;
+use crate::task::{Priority, Task};
+
+// (A) is the most urgent, anything past (C) is low like lines without a
+// priority, which todo.txt sorts after every prioritised line
+fn priority(priority: char) -> Priority {
+    match priority {
+        'A' => Priority::Urgent,
+        'B' => Priority::High,
+        'C' => Priority::Normal,
+        _ => Priority::Low,
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
+
+// One task per line, completed ("x ...") lines are skipped. Understands
+// priorities, +project, @context (as a tag), due:, t: (threshold, used as
+// start) and est: for the estimate, which defaults to an hour.
+pub fn import(contents: &str) -> Result<List> {
+    let mut list = List::new();
+    let mut projects: HashMap<String, usize> = HashMap::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("x ") {
+            continue;
+        }
+        let error = |message: &str| anyhow!("Line {}: {}", number + 1, message);
+        let mut words = line.split_whitespace().peekable();
+
+        let mut task_priority = Priority::Low;
+        if let Some(letter) = words.peek().and_then(|word| {
+            let letter = word.strip_prefix('(')?.strip_suffix(')')?;
+            (letter.len() == 1).then(|| letter.chars().next().unwrap())
+        }) {
+            task_priority = priority(letter);
+            words.next();
+        }
+        // Creation date
+        if words.peek().is_some_and(|word| parse_date(word).is_some()) {
+            words.next();
+        }
+
+        let mut name = Vec::new();
+        let mut contexts = Vec::new();
+        let mut extra = Vec::new();
+        let mut task_projects = Vec::new();
+        let mut start = None;
+        let mut deadline = None;
+        let mut estimate = TimeDelta::hours(1);
+        for word in words {
+            if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty()) {
+                task_projects.push(project);
+            } else if let Some(context) = word.strip_prefix('@').filter(|c| !c.is_empty()) {
+                contexts.push(context);
+            } else if let Some(due) = word.strip_prefix("due:") {
+                let date = parse_date(due).ok_or_else(|| error("Invalid due date"))?;
+                deadline = Some(date.and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap()));
+            } else if let Some(threshold) = word.strip_prefix("t:") {
+                let date = parse_date(threshold).ok_or_else(|| error("Invalid threshold date"))?;
+                start = Some(date.and_time(NaiveTime::MIN));
+            } else if let Some(est) = word.strip_prefix("est:") {
+                estimate = parse_duration(est).ok_or_else(|| error("Invalid estimate"))?;
+            } else if word.contains(':') && !word.contains("://") {
+                extra.push(word);
+            } else {
+                name.push(word);
+            }
+        }
+        if name.is_empty() {
+            return Err(error("Task has no name"));
+        }
+
+        let mut task = Task::new(
+            name.join(" "),
+            extra.join(" "),
+            estimate,
+            None,
+            start,
+            deadline,
+        );
+        // Importing the file again matches up instead of duplicating, only the
+        // name and projects count so editing dates or contexts keeps the task
+        let identity = format!(
+            "{} {}",
+            task.name,
+            task_projects
+                .iter()
+                .sorted()
+                .map(|project| format!("+{}", project))
+                .join(" ")
+        );
+        task.uid = Uuid::new_v5(&Uuid::NAMESPACE_OID, identity.as_bytes());
+        task.set_tags(contexts);
+        task.priority = task_priority;
+        let id = list.insert_task(task);
+        for project in task_projects {
+            let project = project_task(&mut list, &mut projects, project);
+            list.add_subtask(project, id);
+        }
+    }
+    Ok(list)
+}
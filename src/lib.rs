@@ -1,4 +1,17 @@
+pub mod alerts;
+pub mod api;
+pub mod config;
+pub mod export;
+pub mod hooks;
+pub mod import;
 pub mod list;
 pub mod manager;
+pub mod merge;
+pub mod query;
+pub mod report;
 pub mod schedule;
+pub mod search;
+pub mod storage;
 pub mod task;
+pub mod tui;
+pub mod view;
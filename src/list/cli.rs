@@ -1,12 +1,17 @@
 use std::collections::HashMap;
 
-use crate::task::Task;
-use anyhow::Result;
-use anyhow::anyhow;
-use inquire::{Confirm, MultiSelect, Select};
+use crate::hooks::Hook;
+use crate::query::Query;
+use crate::schedule::Itinerary;
+use crate::schedule::cli::{select_date, select_day};
+use crate::task::{Priority, Status, Task};
+use anyhow::{Result, anyhow};
+use inquire::validator::Validation;
+use inquire::{Confirm, MultiSelect, Select, Text};
 use itertools::Itertools;
 
 use super::List;
+use super::journal::Event;
 
 impl List {
     fn get_tasks<F: FnMut(&Task) -> bool>(
@@ -17,12 +22,25 @@ impl List {
         tasks
             .into_iter()
             .filter_map(|id| {
-                if filter(self.tasks.get(&id).unwrap()) {
+                let task = self.tasks.get(&id).unwrap();
+                if filter(task) {
                     let stress = self.stress(id);
+                    let priority = if task.priority == Priority::Normal {
+                        String::new()
+                    } else {
+                        format!(" [{}]", task.priority)
+                    };
+                    let status = if task.status == Status::Todo {
+                        String::new()
+                    } else {
+                        format!(" <{}>", task.status)
+                    };
                     return Some((
                         format!(
-                            "{} ({:.2})",
-                            self.tasks.get(&id).unwrap().name.clone(),
+                            "{}{}{} ({:.2})",
+                            task.name.clone(),
+                            priority,
+                            status,
                             stress
                         ),
                         id,
@@ -31,21 +49,24 @@ impl List {
                 }
                 None
             })
-            .sorted_by(|(_, _, a), (_, _, b)| b.partial_cmp(a).unwrap())
+            // Priority breaks ties between equally stressful tasks
+            .sorted_by(|(_, a_id, a), (_, b_id, b)| {
+                b.partial_cmp(a)
+                    .unwrap()
+                    .then(self.tasks[b_id].priority.cmp(&self.tasks[a_id].priority))
+            })
             .collect()
     }
 
     pub fn pick_task<F: FnMut(&Task) -> bool>(&self, filter: F) -> usize {
-        match Select::new("Search Type", vec!["Tree", "List"])
+        match Select::new("Search Type", vec!["Tree", "List", "Query", "Tag"])
             .prompt()
             .unwrap()
         {
-            "Tree" => {
-                return self.pick_task_tree(filter);
-            }
-            "List" => {
-                return self.pick_task_list(filter);
-            }
+            "Tree" => self.pick_task_tree(filter),
+            "List" => self.pick_task_list(filter),
+            "Query" => self.pick_task_query(filter),
+            "Tag" => self.pick_task_tag(filter),
             _ => {
                 unreachable!();
             }
@@ -74,6 +95,51 @@ impl List {
         return *map.get(&task).unwrap();
     }
 
+    fn pick_task_query<F: FnMut(&Task) -> bool>(&self, mut filter: F) -> usize {
+        loop {
+            let query = Text::new("Query")
+                .with_help_message(
+                    "e.g. due<7d stress>2 started !has-subtasks under:\"Thesis\" tag:work",
+                )
+                .with_validator(|input: &str| {
+                    Ok(match Query::parse(input) {
+                        Ok(_) => Validation::Valid,
+                        Err(error) => Validation::Invalid(error.to_string().into()),
+                    })
+                })
+                .prompt()
+                .unwrap();
+            let query = Query::parse(&query).unwrap();
+            if !self
+                .tasks
+                .values()
+                .any(|task| filter(task) && query.matches(self, task.id))
+            {
+                println!("No tasks match");
+                continue;
+            }
+            return self.pick_task_list(|task: &Task| filter(task) && query.matches(self, task.id));
+        }
+    }
+
+    fn pick_task_tag<F: FnMut(&Task) -> bool>(&self, mut filter: F) -> usize {
+        let tags: Vec<String> = self
+            .all_tags()
+            .into_iter()
+            .filter(|tag| {
+                self.tasks
+                    .values()
+                    .any(|task| task.tags.contains(tag) && filter(task))
+            })
+            .collect();
+        if tags.is_empty() {
+            println!("No tagged tasks to pick from");
+            return self.pick_task_list(filter);
+        }
+        let tag = Select::new("Select a Tag", tags).prompt().unwrap();
+        self.pick_task_list(|task: &Task| task.tags.contains(&tag) && filter(task))
+    }
+
     fn valid_children<F: FnMut(&Task) -> bool>(&self, id: usize, mut filter: F) -> usize {
         let children = self.get_all_children(id);
         return children
@@ -137,11 +203,52 @@ impl List {
     }
 
     pub fn modify_task(&mut self, id: usize) {
-        self.tasks.get_mut(&id).unwrap().update_task();
+        let before = self.tasks.get(&id).unwrap().clone();
+        let mut after = before.clone();
+        after.update_task(&self.all_tags());
+        self.record(Event::ModifyTask {
+            before: Box::new(before),
+            after: Box::new(after),
+        });
         // Assign Parents
         self.update_supertasks(id);
         // Assign subtasks
         self.update_subtasks(id);
+        self.run_hook(Hook::Modify, id);
+    }
+
+    pub fn view_task(&self, id: usize) {
+        let task = self.tasks.get(&id).unwrap();
+        let earliest = self
+            .schedule
+            .earliest_complete(self.effective_time(id), task.start)
+            .map(|earliest| earliest.to_string())
+            .unwrap_or(String::from("Not enough scheduled time"));
+        println!("{}Earliest Completion: {}", task, earliest);
+    }
+
+    // What to do with a task found through search or a view
+    pub fn task_action(&mut self, id: usize) {
+        match Select::new("Action", vec!["View", "Modify", "Complete", "Set Status"])
+            .prompt()
+            .unwrap()
+        {
+            "View" => self.view_task(id),
+            "Modify" => self.modify_task(id),
+            "Complete" => {
+                if !self.tasks.get(&id).unwrap().started() {
+                    println!("Task hasn't started yet");
+                } else if let Err(error) = self.complete_task(id) {
+                    println!("{}", error);
+                }
+            }
+            "Set Status" => {
+                if let Err(error) = self.set_task_status(id) {
+                    println!("{}", error);
+                }
+            }
+            _ => unreachable!(),
+        }
     }
 
     pub fn complete_task(&mut self, id: usize) -> Result<()> {
@@ -153,14 +260,60 @@ impl List {
             println!("Skipping");
             return Ok(());
         }
-        for subtask in self.tasks.get(&id).unwrap().subtasks.iter() {
-            return Err(anyhow!(
-                "Error subtask \"{}\" is not complete",
-                self.tasks.get(subtask).unwrap().name
-            ));
+        self.complete(id)
+    }
+
+    pub fn set_task_status(&mut self, id: usize) -> Result<()> {
+        let current = self.tasks.get(&id).unwrap().status;
+        let status = Select::new("Status", Status::ALL.to_vec())
+            .with_starting_cursor(Status::ALL.iter().position(|s| *s == current).unwrap())
+            .with_help_message(
+                "Waiting tasks add less stress, done and cancelled tasks are removed",
+            )
+            .prompt()
+            .unwrap();
+        match status {
+            Status::Done if !self.tasks.get(&id).unwrap().started() => {
+                Err(anyhow!("Task hasn't started yet"))
+            }
+            Status::Done => self.complete_task(id),
+            Status::Cancelled => {
+                if !Confirm::new("Are you sure you'd like to cancel this task?")
+                    .with_default(false)
+                    .prompt()
+                    .unwrap()
+                {
+                    println!("Skipping");
+                    return Ok(());
+                }
+                self.set_status(id, status)
+            }
+            _ => self.set_status(id, status),
         }
-        self.remove_task(id);
-        Ok(())
+    }
+
+    pub fn update_schedule(&mut self) {
+        let date = select_date();
+        let before = self.schedule.get_scheduled_itinerary(date).cloned();
+        let mut after = before.clone().unwrap_or_else(Itinerary::new);
+        after.update();
+        self.record(Event::SetItinerary {
+            date,
+            before,
+            after: Some(after),
+        });
+    }
+
+    pub fn update_default_schedule(&mut self) {
+        let weekday = select_day();
+        let before = self.schedule.get_default_itinerary(weekday).clone();
+        let mut after = before.clone();
+        after.update();
+        self.record(Event::SetDefaultItinerary {
+            weekday,
+            before,
+            after,
+        });
     }
 
     pub fn update_subtasks(&mut self, id: usize) {
@@ -285,3 +438,17 @@ impl List {
         });
     }
 }
+
+pub fn print_tag_totals(list: &List) {
+    let totals = list.tag_totals();
+    if totals.is_empty() {
+        println!("No tagged tasks");
+        return;
+    }
+    for total in totals {
+        println!(
+            "{}: {} tasks, {:.1} hours, stress {:.2}",
+            total.tag, total.tasks, total.hours, total.stress
+        );
+    }
+}
@@ -0,0 +1,426 @@
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeDelta, Weekday};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::List;
+use crate::{
+    query::view::SavedView,
+    schedule::{Itinerary, WEEK},
+    task::Task,
+};
+
+// Entries kept in the data file, past this the oldest half is compacted
+const MAX_ENTRIES: usize = 200;
+
+// Events hold enough state to be reversed, so undo never needs a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Event {
+    AddTask(Task),
+    RemoveTask(Task),
+    CompleteTask(Task),
+    AddSubtask {
+        id: usize,
+        subtask: usize,
+    },
+    RemoveSubtask {
+        id: usize,
+        subtask: usize,
+    },
+    ModifyTask {
+        before: Box<Task>,
+        after: Box<Task>,
+    },
+    SetItinerary {
+        date: NaiveDate,
+        before: Option<Itinerary>,
+        after: Option<Itinerary>,
+    },
+    SetDefaultItinerary {
+        weekday: Weekday,
+        before: Itinerary,
+        after: Itinerary,
+    },
+    SetView {
+        name: String,
+        before: Option<SavedView>,
+        after: Option<SavedView>,
+    },
+}
+
+impl Event {
+    pub fn inverse(&self) -> Event {
+        match self.clone() {
+            Event::AddTask(task) => Event::RemoveTask(task),
+            Event::RemoveTask(task) | Event::CompleteTask(task) => Event::AddTask(task),
+            Event::AddSubtask { id, subtask } => Event::RemoveSubtask { id, subtask },
+            Event::RemoveSubtask { id, subtask } => Event::AddSubtask { id, subtask },
+            Event::ModifyTask { before, after } => Event::ModifyTask {
+                before: after,
+                after: before,
+            },
+            Event::SetItinerary {
+                date,
+                before,
+                after,
+            } => Event::SetItinerary {
+                date,
+                before: after,
+                after: before,
+            },
+            Event::SetDefaultItinerary {
+                weekday,
+                before,
+                after,
+            } => Event::SetDefaultItinerary {
+                weekday,
+                before: after,
+                after: before,
+            },
+            Event::SetView {
+                name,
+                before,
+                after,
+            } => Event::SetView {
+                name,
+                before: after,
+                after: before,
+            },
+        }
+    }
+}
+
+// All the events produced by a single action, undone and redone together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub time: NaiveDateTime,
+    pub events: Vec<Event>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    entries: Vec<Entry>,
+    // Entries before the cursor are applied, the rest can be redone
+    cursor: usize,
+    // Entries before the floor rebuild data that predates the journal
+    floor: usize,
+    // Tasks completed in entries that have been compacted away
+    #[serde(default)]
+    completed: Vec<Task>,
+    #[serde(skip)]
+    pending: Vec<Event>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Journal::default()
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries[..self.cursor]
+    }
+
+    // Brings the task and view snapshots up to date like the live ones, so undo never
+    // restores old data. Tasks recorded before uids existed get the same uid as
+    // the live task. True if anything changed.
+    pub(super) fn migrate(&mut self, uids: &mut HashMap<usize, Uuid>) -> bool {
+        let mut tasks: Vec<&mut Task> = Vec::new();
+        let mut views: Vec<&mut SavedView> = Vec::new();
+        for entry in self.entries.iter_mut() {
+            for event in entry.events.iter_mut() {
+                match event {
+                    Event::AddTask(task) | Event::RemoveTask(task) | Event::CompleteTask(task) => {
+                        tasks.push(task)
+                    }
+                    Event::ModifyTask { before, after } => {
+                        tasks.push(&mut **before);
+                        tasks.push(&mut **after);
+                    }
+                    Event::SetView { before, after, .. } => {
+                        views.extend(before.iter_mut().chain(after.iter_mut()))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        let mut migrated = false;
+        for task in tasks {
+            if task.uid.is_nil() {
+                task.uid = *uids.entry(task.id).or_insert_with(|| task.legacy_uid());
+                migrated = true;
+            }
+            migrated |= task.migrate_tags();
+        }
+        for view in views {
+            migrated |= view.migrate();
+        }
+        migrated
+    }
+
+    fn record(&mut self, event: Event) {
+        self.pending.push(event);
+    }
+
+    fn commit(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        self.entries.truncate(self.cursor);
+        self.entries.push(Entry {
+            time: Local::now().naive_local(),
+            events: std::mem::take(&mut self.pending),
+        });
+        self.cursor = self.entries.len();
+    }
+
+    fn undo(&mut self) -> Option<Vec<Event>> {
+        self.commit();
+        if self.cursor <= self.floor {
+            return None;
+        }
+        self.cursor -= 1;
+        Some(
+            self.entries[self.cursor]
+                .events
+                .iter()
+                .rev()
+                .map(Event::inverse)
+                .collect(),
+        )
+    }
+
+    fn redo(&mut self) -> Option<Vec<Event>> {
+        self.commit();
+        let entry = self.entries.get(self.cursor)?;
+        self.cursor += 1;
+        Some(entry.events.clone())
+    }
+}
+
+impl List {
+    pub(super) fn record(&mut self, event: Event) {
+        self.apply(&event);
+        self.journal.record(event);
+    }
+
+    pub fn commit(&mut self) {
+        self.journal.commit();
+        // Only once nothing is left to redo, so compacting never drops redo
+        if self.journal.entries.len() > MAX_ENTRIES
+            && self.journal.cursor == self.journal.entries.len()
+        {
+            self.compact(MAX_ENTRIES / 2);
+        }
+    }
+
+    // Folds all but the newest entries into one entry holding the state they
+    // built, undo can't go back past it anymore
+    fn compact(&mut self, keep: usize) {
+        let cut = self.journal.entries.len() - keep;
+        let mut base = List::from_parts(self.tasks.clone(), self.schedule.clone());
+        base.views = self.views.clone();
+        for entry in self.journal.entries[cut..].iter().rev() {
+            for event in entry.events.iter().rev() {
+                base.apply(&event.inverse());
+            }
+        }
+        base.seed_journal();
+
+        let mut journal = base.journal;
+        journal.completed = std::mem::take(&mut self.journal.completed);
+        for entry in self.journal.entries.drain(..cut) {
+            for event in entry.events {
+                if let Event::CompleteTask(task) = event {
+                    journal.completed.push(task);
+                }
+            }
+        }
+        journal.entries.append(&mut self.journal.entries);
+        journal.cursor = journal.entries.len();
+        self.journal = journal;
+    }
+
+    // Takes back everything recorded since the last commit
+    pub fn discard(&mut self) {
+        let events = std::mem::take(&mut self.journal.pending);
+        for event in events.iter().rev() {
+            self.apply(&event.inverse());
+        }
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if let Some(events) = self.journal.undo() {
+            events.iter().for_each(|event| self.apply(event));
+            return true;
+        }
+        false
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if let Some(events) = self.journal.redo() {
+            events.iter().for_each(|event| self.apply(event));
+            return true;
+        }
+        false
+    }
+
+    // Estimated time of the tasks under id that have been completed. Walking
+    // backwards picks up subtasks of tasks that were completed later on.
+    pub fn completed_time(&self, id: usize) -> TimeDelta {
+        let mut under = self.get_all_children(id);
+        let mut time = TimeDelta::zero();
+        let completed = self
+            .journal
+            .entries()
+            .iter()
+            .rev()
+            .flat_map(|entry| entry.events.iter().rev())
+            .filter_map(|event| match event {
+                Event::CompleteTask(task) => Some(task),
+                _ => None,
+            })
+            .chain(self.journal.completed.iter().rev());
+        for task in completed {
+            if !task.supertasks.is_disjoint(&under) {
+                under.insert(task.id);
+                time += task.estimated_time;
+            }
+        }
+        time
+    }
+
+    // Rebuild the state a journal describes from scratch
+    pub fn replay(journal: &Journal) -> List {
+        let mut list = List::new();
+        for entry in journal.entries() {
+            entry.events.iter().for_each(|event| list.apply(event));
+        }
+        list.journal = journal.clone();
+        list
+    }
+
+    // Data saved before the journal existed gets recorded as the events that
+    // would have built it, so replaying never loses tasks.
+    pub(super) fn seed_journal(&mut self) -> bool {
+        if !self.journal.entries.is_empty() {
+            return false;
+        }
+        let mut ids: Vec<usize> = self.tasks.keys().cloned().collect();
+        ids.sort();
+        for &id in ids.iter() {
+            let mut task = self.tasks.get(&id).unwrap().clone();
+            task.subtasks.clear();
+            task.supertasks.clear();
+            self.journal.record(Event::AddTask(task));
+        }
+        for &id in ids.iter() {
+            let mut subtasks: Vec<usize> = self
+                .tasks
+                .get(&id)
+                .unwrap()
+                .subtasks
+                .iter()
+                .cloned()
+                .collect();
+            subtasks.sort();
+            for subtask in subtasks {
+                self.journal.record(Event::AddSubtask { id, subtask });
+            }
+        }
+        for (date, itinerary) in self.schedule.scheduled_itineraries() {
+            self.journal.record(Event::SetItinerary {
+                date,
+                before: None,
+                after: Some(itinerary.clone()),
+            });
+        }
+        for weekday in WEEK {
+            self.journal.record(Event::SetDefaultItinerary {
+                weekday,
+                before: Itinerary::new(),
+                after: self.schedule.get_default_itinerary(weekday).clone(),
+            });
+        }
+        for (name, view) in self.views.iter() {
+            self.journal.record(Event::SetView {
+                name: name.clone(),
+                before: None,
+                after: Some(view.clone()),
+            });
+        }
+        self.journal.commit();
+        self.journal.floor = self.journal.cursor;
+        true
+    }
+
+    fn apply(&mut self, event: &Event) {
+        match event {
+            Event::AddTask(task) => {
+                let id = task.id;
+                self.id_counter = self.id_counter.max(id + 1);
+                self.tasks.insert(id, task.clone());
+                for subtask in task.subtasks.iter() {
+                    if let Some(subtask) = self.tasks.get_mut(subtask) {
+                        subtask.supertasks.insert(id);
+                    }
+                }
+                for supertask in task.supertasks.iter() {
+                    if let Some(supertask) = self.tasks.get_mut(supertask) {
+                        supertask.subtasks.insert(id);
+                    }
+                }
+            }
+            Event::RemoveTask(task) | Event::CompleteTask(task) => {
+                let task = self.tasks.remove(&task.id).unwrap();
+                for subtask in task.subtasks.iter() {
+                    self.tasks
+                        .get_mut(subtask)
+                        .unwrap()
+                        .supertasks
+                        .remove(&task.id);
+                }
+                for supertask in task.supertasks.iter() {
+                    self.tasks
+                        .get_mut(supertask)
+                        .unwrap()
+                        .subtasks
+                        .remove(&task.id);
+                }
+            }
+            Event::AddSubtask { id, subtask } => {
+                self.tasks.get_mut(id).unwrap().subtasks.insert(*subtask);
+                self.tasks.get_mut(subtask).unwrap().supertasks.insert(*id);
+            }
+            Event::RemoveSubtask { id, subtask } => {
+                self.tasks.get_mut(id).unwrap().subtasks.remove(subtask);
+                self.tasks.get_mut(subtask).unwrap().supertasks.remove(id);
+            }
+            Event::ModifyTask { after, .. } => {
+                // Links have their own events, only the fields are replaced
+                let task = self.tasks.get_mut(&after.id).unwrap();
+                let subtasks = std::mem::take(&mut task.subtasks);
+                let supertasks = std::mem::take(&mut task.supertasks);
+                *task = (**after).clone();
+                task.subtasks = subtasks;
+                task.supertasks = supertasks;
+            }
+            Event::SetItinerary { date, after, .. } => {
+                if let Some(itinerary) = after {
+                    self.schedule.set_itinerary(*date, itinerary.clone());
+                } else {
+                    self.schedule.remove_itinerary(*date);
+                }
+            }
+            Event::SetDefaultItinerary { weekday, after, .. } => {
+                self.schedule.set_default_itinerary(*weekday, after.clone());
+            }
+            Event::SetView { name, after, .. } => {
+                if let Some(view) = after {
+                    self.views.insert(name.clone(), view.clone());
+                } else {
+                    self.views.remove(name);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+
+use super::List;
+use crate::merge::{Conflict, MergeReport, Resolver, Side, merge_set, merge_value};
+use crate::query::view::SavedView;
+use crate::schedule::Schedule;
+use crate::task::Task;
+
+fn describe_view(view: Option<&SavedView>) -> String {
+    view.map(|view| view.to_string())
+        .unwrap_or(String::from("deleted"))
+}
+
+impl List {
+    fn same_task(base: &List, ours: &Task, theirs: &Task) -> bool {
+        if !ours.uid.is_nil() && !theirs.uid.is_nil() {
+            return ours.uid == theirs.uid;
+        }
+        base.tasks.contains_key(&theirs.id) || ours.name == theirs.name
+    }
+
+    fn remap(&mut self, ids: &HashMap<usize, usize>) {
+        let remap = |id: &usize| *ids.get(id).unwrap_or(id);
+        self.tasks = std::mem::take(&mut self.tasks)
+            .into_values()
+            .map(|mut task| {
+                task.id = remap(&task.id);
+                task.subtasks = task.subtasks.iter().map(remap).collect();
+                task.supertasks = task.supertasks.iter().map(remap).collect();
+                (task.id, task)
+            })
+            .collect();
+    }
+
+    // Three way merge of two lists that diverged from base. Tasks are matched
+    // by uid, or by id for files from before uids, and their tasks that reuse
+    // an id for something new get renumbered.
+    pub fn merge(
+        base: &List,
+        ours: &List,
+        mut theirs: List,
+        resolve: &mut Resolver,
+    ) -> (List, MergeReport) {
+        let mut report = MergeReport::default();
+        let mut id_counter = [base, ours, &theirs]
+            .iter()
+            .flat_map(|list| list.tasks.keys().map(|id| id + 1).chain([list.id_counter]))
+            .max()
+            .unwrap_or(0);
+
+        let mut ids = HashMap::new();
+        for task in theirs.tasks.values().sorted_by_key(|task| task.id) {
+            let matched = if task.uid.is_nil() {
+                None
+            } else {
+                ours.find_uid(task.uid).or_else(|| base.find_uid(task.uid))
+            };
+            let id = matched.unwrap_or_else(|| {
+                let collides = [ours, base].iter().any(|list| {
+                    list.tasks
+                        .get(&task.id)
+                        .is_some_and(|other| !List::same_task(base, other, task))
+                });
+                if collides {
+                    id_counter += 1;
+                    report.renumbered.push((task.id, id_counter - 1));
+                    return id_counter - 1;
+                }
+                task.id
+            });
+            ids.insert(task.id, id);
+        }
+        theirs.remap(&ids);
+
+        let mut conflicts = Vec::new();
+        let mut resolve = |conflict: &Conflict| {
+            let side = resolve(conflict);
+            conflicts.push((conflict.clone(), side));
+            side
+        };
+
+        let ids: Vec<usize> = base
+            .tasks
+            .keys()
+            .chain(ours.tasks.keys())
+            .chain(theirs.tasks.keys())
+            .cloned()
+            .unique()
+            .sorted()
+            .collect();
+        let empty = HashSet::new();
+        let mut subtasks: HashMap<usize, HashSet<usize>> = HashMap::new();
+        let mut list = List::new();
+        for id in ids {
+            let base_task = base.tasks.get(&id);
+            let task = match (ours.tasks.get(&id), theirs.tasks.get(&id)) {
+                (Some(our_task), Some(their_task)) => {
+                    let base_subtasks = base_task.map(|task| &task.subtasks).unwrap_or(&empty);
+                    subtasks.insert(
+                        id,
+                        merge_set(base_subtasks, &our_task.subtasks, &their_task.subtasks),
+                    );
+                    Some(Task::merge(base_task, our_task, their_task, &mut resolve))
+                }
+                (Some(task), None) | (None, Some(task)) => {
+                    let side = if ours.tasks.contains_key(&id) {
+                        Side::Ours
+                    } else {
+                        Side::Theirs
+                    };
+                    let kept = match base_task {
+                        None => true,
+                        Some(base_task) if base_task.fields_eq(task) => false,
+                        Some(_) => {
+                            let conflict = Conflict {
+                                subject: format!("Task {} \"{}\"", id, task.name),
+                                ours: String::from(if side == Side::Ours {
+                                    "modified"
+                                } else {
+                                    "removed"
+                                }),
+                                theirs: String::from(if side == Side::Theirs {
+                                    "modified"
+                                } else {
+                                    "removed"
+                                }),
+                            };
+                            resolve(&conflict) == side
+                        }
+                    };
+                    if kept {
+                        subtasks.insert(id, task.subtasks.clone());
+                        let mut task = task.clone();
+                        task.subtasks.clear();
+                        task.supertasks.clear();
+                        Some(task)
+                    } else {
+                        None
+                    }
+                }
+                (None, None) => None,
+            };
+            if let Some(task) = task {
+                list.tasks.insert(id, task);
+            }
+        }
+
+        // Links ours already had go in first so theirs can't form a cycle with them
+        let links: Vec<(usize, usize)> = subtasks
+            .iter()
+            .flat_map(|(&id, subtasks)| subtasks.iter().map(move |&subtask| (id, subtask)))
+            .filter(|(id, subtask)| list.tasks.contains_key(id) && list.tasks.contains_key(subtask))
+            .sorted_by_key(|&(id, subtask)| {
+                let ours_link = ours
+                    .tasks
+                    .get(&id)
+                    .is_some_and(|task| task.subtasks.contains(&subtask));
+                (!ours_link, id, subtask)
+            })
+            .collect();
+        for (id, subtask) in links {
+            if list.get_all_children(subtask).contains(&id) {
+                report.notes.push(format!(
+                    "Dropped subtask {} of {} since it would form a cycle",
+                    subtask, id
+                ));
+                continue;
+            }
+            list.tasks.get_mut(&id).unwrap().subtasks.insert(subtask);
+            list.tasks.get_mut(&subtask).unwrap().supertasks.insert(id);
+        }
+
+        list.id_counter = id_counter;
+        list.schedule = Schedule::merge(
+            &base.schedule,
+            &ours.schedule,
+            &theirs.schedule,
+            &mut resolve,
+        );
+        let names: Vec<String> = base
+            .views
+            .keys()
+            .chain(ours.views.keys())
+            .chain(theirs.views.keys())
+            .unique()
+            .cloned()
+            .collect();
+        for name in names {
+            let view = merge_value(
+                Some(&base.views.get(&name).cloned()),
+                &ours.views.get(&name).cloned(),
+                &theirs.views.get(&name).cloned(),
+                || Conflict {
+                    subject: format!("view \"{}\"", name),
+                    ours: describe_view(ours.views.get(&name)),
+                    theirs: describe_view(theirs.views.get(&name)),
+                },
+                &mut resolve,
+            );
+            if let Some(view) = view {
+                list.views.insert(name, view);
+            }
+        }
+        list.migrate();
+        report.conflicts = conflicts;
+        (list, report)
+    }
+}
@@ -1,15 +1,34 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 pub mod cli;
+pub mod journal;
+mod merge;
 mod stress;
+pub mod tags;
 
-use crate::{schedule::Schedule, task::Task};
+use crate::{
+    hooks::{Hook, Hooks},
+    query::view::SavedView,
+    schedule::{Itinerary, Schedule},
+    task::{Status, Task},
+};
+use anyhow::{Result, anyhow};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use journal::{Event, Journal};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct List {
     pub tasks: HashMap<usize, Task>,
     id_counter: usize,
     pub schedule: Schedule,
+    #[serde(default)]
+    pub journal: Journal,
+    #[serde(default)]
+    pub views: BTreeMap<String, SavedView>,
+    // Comes from the config, not the data file
+    #[serde(skip)]
+    pub hooks: Hooks,
 }
 
 impl List {
@@ -18,36 +37,146 @@ impl List {
             tasks: HashMap::new(),
             id_counter: 0,
             schedule: Schedule::new(),
+            journal: Journal::new(),
+            views: BTreeMap::new(),
+            hooks: Hooks::default(),
         }
     }
 
-    pub fn add_task(&mut self, mut task: Task) {
-        task.initialize(self.id_counter).unwrap();
-        self.id_counter += 1;
-        let id = task.id;
-        self.tasks.insert(id, task);
+    pub fn from_parts(tasks: HashMap<usize, Task>, schedule: Schedule) -> Self {
+        List {
+            id_counter: tasks.keys().map(|id| id + 1).max().unwrap_or(0),
+            tasks,
+            schedule,
+            journal: Journal::new(),
+            views: BTreeMap::new(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    // Brings data saved by older versions up to date, true if anything changed
+    pub fn migrate(&mut self) -> bool {
+        let mut migrated = false;
+        let mut uids: HashMap<usize, Uuid> = HashMap::new();
+        for task in self.tasks.values_mut() {
+            if task.uid.is_nil() {
+                task.uid = task.legacy_uid();
+                migrated = true;
+            }
+            uids.insert(task.id, task.uid);
+            migrated |= task.migrate_tags();
+        }
+        for view in self.views.values_mut() {
+            migrated |= view.migrate();
+        }
+        migrated |= self.journal.migrate(&mut uids);
+        self.seed_journal() || migrated
+    }
+
+    pub fn find_uid(&self, uid: Uuid) -> Option<usize> {
+        self.tasks
+            .values()
+            .find(|task| task.uid == uid)
+            .map(|task| task.id)
+    }
+
+    pub fn add_task(&mut self, task: Task) {
+        let id = self.insert_task(task);
         self.update_supertasks(id);
         self.update_subtasks(id);
+        self.run_hook(Hook::Add, id);
+    }
+
+    pub fn insert_task(&mut self, mut task: Task) -> usize {
+        task.initialize(self.id_counter).unwrap();
+        task.subtasks.clear();
+        task.supertasks.clear();
+        let id = task.id;
+        self.record(Event::AddTask(task));
+        id
     }
 
     pub fn remove_task(&mut self, id: usize) {
-        let task = self.tasks.remove(&id).unwrap();
-        for subtask in task.subtasks.iter() {
-            self.tasks.get_mut(subtask).unwrap().supertasks.remove(&id);
+        let task = self.tasks.get(&id).unwrap().clone();
+        self.record(Event::RemoveTask(task));
+    }
+
+    // Replaces the task's fields, its links are left as they are
+    pub fn replace_task(&mut self, task: Task) {
+        let before = self.tasks.get(&task.id).unwrap().clone();
+        self.record(Event::ModifyTask {
+            before: Box::new(before),
+            after: Box::new(task),
+        });
+    }
+
+    // Closing a task would leave its subtasks without it
+    fn check_subtasks(&self, id: usize) -> Result<()> {
+        if let Some(subtask) = self.tasks.get(&id).unwrap().subtasks.iter().next() {
+            return Err(anyhow!(
+                "Error subtask \"{}\" is not complete",
+                self.tasks.get(subtask).unwrap().name
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn complete(&mut self, id: usize) -> Result<()> {
+        self.check_subtasks(id)?;
+        self.run_hook(Hook::Complete, id);
+        let task = self.stamp_status(id, Status::Done);
+        self.record(Event::CompleteTask(task));
+        Ok(())
+    }
+
+    // Drops the task without counting it as completed
+    pub fn cancel(&mut self, id: usize) -> Result<()> {
+        self.check_subtasks(id)?;
+        let task = self.stamp_status(id, Status::Cancelled);
+        self.run_hook(Hook::Modify, id);
+        self.record(Event::RemoveTask(task));
+        Ok(())
+    }
+
+    pub fn set_status(&mut self, id: usize, status: Status) -> Result<()> {
+        match status {
+            Status::Done => self.complete(id),
+            Status::Cancelled => self.cancel(id),
+            _ => {
+                self.stamp_status(id, status);
+                self.run_hook(Hook::Modify, id);
+                Ok(())
+            }
         }
-        for supertask in task.supertasks.iter() {
-            self.tasks.get_mut(supertask).unwrap().subtasks.remove(&id);
+    }
+
+    // Records the status change in the same entry, so undo restores the old status
+    fn stamp_status(&mut self, id: usize, status: Status) -> Task {
+        let mut task = self.tasks.get(&id).unwrap().clone();
+        if task.status != status {
+            task.set_status(status);
+            self.replace_task(task.clone());
         }
+        task
+    }
+
+    // Linking subtask under id would make id its own descendant
+    pub fn would_cycle(&self, id: usize, subtask: usize) -> bool {
+        self.get_all_children(subtask).contains(&id)
     }
 
     pub fn add_subtask(&mut self, id: usize, subtask: usize) {
-        self.tasks.get_mut(&id).unwrap().subtasks.insert(subtask);
-        self.tasks.get_mut(&subtask).unwrap().supertasks.insert(id);
+        if self.tasks.get(&id).unwrap().subtasks.contains(&subtask) {
+            return;
+        }
+        self.record(Event::AddSubtask { id, subtask });
     }
 
     pub fn remove_subtask(&mut self, id: usize, subtask: usize) {
-        self.tasks.get_mut(&id).unwrap().subtasks.remove(&subtask);
-        self.tasks.get_mut(&subtask).unwrap().supertasks.remove(&id);
+        if !self.tasks.get(&id).unwrap().subtasks.contains(&subtask) {
+            return;
+        }
+        self.record(Event::RemoveSubtask { id, subtask });
     }
 
     pub fn add_supertask(&mut self, id: usize, supertask: usize) {
@@ -58,6 +187,74 @@ impl List {
         self.remove_subtask(supertask, id);
     }
 
+    pub fn set_itinerary(&mut self, date: NaiveDate, itinerary: Itinerary) {
+        let before = self.schedule.get_scheduled_itinerary(date).cloned();
+        self.record(Event::SetItinerary {
+            date,
+            before,
+            after: Some(itinerary),
+        });
+    }
+
+    // Goes back to the default schedule for that day
+    pub fn clear_itinerary(&mut self, date: NaiveDate) {
+        let before = self.schedule.get_scheduled_itinerary(date).cloned();
+        if before.is_none() {
+            return;
+        }
+        self.record(Event::SetItinerary {
+            date,
+            before,
+            after: None,
+        });
+    }
+
+    pub fn set_default_itinerary(&mut self, weekday: Weekday, itinerary: Itinerary) {
+        let before = self.schedule.get_default_itinerary(weekday).clone();
+        self.record(Event::SetDefaultItinerary {
+            weekday,
+            before,
+            after: itinerary,
+        });
+    }
+
+    // None deletes the view
+    pub fn set_view(&mut self, name: String, view: Option<SavedView>) {
+        let before = self.views.get(&name).cloned();
+        if before == view {
+            return;
+        }
+        self.record(Event::SetView {
+            name,
+            before,
+            after: view,
+        });
+    }
+
+    // Takes the time out of whatever itinerary applies on each day it spans
+    pub fn mark_busy(&mut self, start: NaiveDateTime, end: NaiveDateTime) {
+        for date in start.date().iter_days() {
+            if date > end.date() || (date == end.date() && end.time() == NaiveTime::MIN) {
+                break;
+            }
+            let from = if date == start.date() {
+                start.time()
+            } else {
+                NaiveTime::MIN
+            };
+            let to = if date == end.date() {
+                end.time()
+            } else {
+                NaiveTime::from_hms_opt(23, 59, 59).unwrap()
+            };
+            let mut itinerary = self.schedule.get_itinerary(date).clone();
+            itinerary.subtract(from, to);
+            if &itinerary != self.schedule.get_itinerary(date) {
+                self.set_itinerary(date, itinerary);
+            }
+        }
+    }
+
     pub fn get_all_parents(&self, id: usize) -> HashSet<usize> {
         let mut parents = HashSet::from([id]);
         let mut stack = vec![id];
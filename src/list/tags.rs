@@ -0,0 +1,49 @@
+use std::collections::{BTreeSet, HashSet};
+
+use super::List;
+
+#[derive(Debug, Clone)]
+pub struct TagTotal {
+    pub tag: String,
+    pub tasks: usize,
+    // Remaining work under the tagged tasks, each task counted once
+    pub hours: f32,
+    pub stress: f32,
+}
+
+impl List {
+    pub fn all_tags(&self) -> BTreeSet<String> {
+        self.tasks
+            .values()
+            .flat_map(|task| task.tags.iter().cloned())
+            .collect()
+    }
+
+    pub fn tag_totals(&self) -> Vec<TagTotal> {
+        self.all_tags()
+            .into_iter()
+            .map(|tag| {
+                let tagged: Vec<usize> = self
+                    .tasks
+                    .values()
+                    .filter(|task| task.tags.contains(&tag))
+                    .map(|task| task.id)
+                    .collect();
+                let under: HashSet<usize> = tagged
+                    .iter()
+                    .flat_map(|&id| self.get_all_children(id))
+                    .collect();
+                TagTotal {
+                    tasks: tagged.len(),
+                    hours: under
+                        .iter()
+                        .map(|id| self.tasks.get(id).unwrap().estimated_time.as_seconds_f32())
+                        .sum::<f32>()
+                        / 3600.0,
+                    stress: tagged.iter().map(|&id| self.stress(id)).sum(),
+                    tag,
+                }
+            })
+            .collect()
+    }
+}
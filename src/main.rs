@@ -1,5 +1,6 @@
-use todo::manager::spin;
+use clap::Parser;
+use todo::manager::{args::Args, run};
 
-fn main() {
-    spin();
+fn main() -> anyhow::Result<()> {
+    run(Args::parse())
 }
@@ -0,0 +1,158 @@
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+use crate::export::csv::Column;
+
+#[derive(Debug, Parser)]
+#[command(about = "Stress aware todo list")]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Json,
+    /// iCalendar with tasks as todos and timeblocks as events
+    Ics,
+    /// One row per task for spreadsheets
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportFormat {
+    Json,
+    Todotxt,
+    Taskwarrior,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportMode {
+    /// Throw away the current list
+    Replace,
+    /// Merge tasks into the current list, matching them by uid
+    Merge,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Three way merge of diverged data files, the result is written to ours
+    Merge {
+        base: PathBuf,
+        ours: PathBuf,
+        theirs: PathBuf,
+        /// Write the result here instead of over ours
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Export the whole list
+    Export {
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Columns for csv, all of them by default
+        #[arg(short, long, value_enum, value_delimiter = ',')]
+        columns: Vec<Column>,
+        /// Only tasks matching this query, e.g. "due<7d !has-subtasks"
+        #[arg(long)]
+        filter: Option<String>,
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Import a list exported by `todo export`, a todo.txt file or a
+    /// Taskwarrior export
+    Import {
+        file: PathBuf,
+        #[arg(short, long, value_enum, default_value_t = ImportFormat::Json)]
+        format: ImportFormat,
+        #[arg(short, long, value_enum, default_value_t = ImportMode::Merge)]
+        mode: ImportMode,
+        /// Show what would be imported without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Taskwarrior UDA holding the estimate
+        #[arg(long, default_value = "estimate")]
+        estimate_uda: String,
+    },
+    /// Take the events in an .ics file out of the schedule
+    Busy {
+        file: PathBuf,
+        /// How far ahead recurring events are expanded
+        #[arg(long, default_value_t = 28)]
+        days: i64,
+        /// List the busy times without changing the schedule
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Summarise the task tree with stress, deadlines and progress
+    Report {
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Markdown)]
+        format: ReportFormat,
+        /// Only this task and its subtasks
+        #[arg(long)]
+        subtree: Option<usize>,
+        /// Only tasks due within this many days
+        #[arg(long)]
+        due_within: Option<i64>,
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Graphviz DOT of the task hierarchy, colored by stress
+    Graph {
+        /// Only this task and its subtasks
+        #[arg(long)]
+        subtree: Option<usize>,
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Serve the task list as a JSON API on localhost
+    Serve {
+        #[arg(short, long, default_value_t = 7878)]
+        port: u16,
+    },
+    /// Answer JSON-RPC requests on stdin, one per line
+    Rpc,
+    /// Exit non-zero when stress is over the configured alert thresholds
+    Check {
+        /// Only set the exit code
+        #[arg(short, long)]
+        quiet: bool,
+    },
+    /// Full-screen task tree, details and today's schedule
+    Tui,
+    /// Today's schedule, tasks starting or due, overdue and most stressful
+    Today,
+    /// Grid of a week's schedule with task starts and deadlines
+    Week {
+        /// Any day in the week to show, defaults to today
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+    /// Chart of when tasks are expected to finish against their deadlines
+    Gantt {
+        /// Only this task and its subtasks
+        #[arg(long)]
+        subtree: Option<usize>,
+        /// Columns used for the bars
+        #[arg(short, long, default_value_t = 60)]
+        width: usize,
+    },
+    /// Fuzzy search task names and descriptions, best matches first
+    Search { query: String },
+    /// Tasks matching a query, e.g. due<7d stress>2 started !has-subtasks
+    /// under:"Thesis" tag:work
+    List { query: Vec<String> },
+    /// Tasks in a saved view, or the saved views when no name is given
+    View { name: Option<String> },
+    /// Remaining hours and stress per tag
+    Tags,
+}
@@ -1,29 +1,50 @@
 use anyhow::Result;
+use chrono::Local;
 use inquire::Select;
 
 use crate::{
-    list::List,
+    list::{List, cli::print_tag_totals},
+    query::cli::views_menu,
+    schedule::cli::select_date,
+    search::cli::search_tasks,
+    storage::Storage,
     task::{Task, cli::create_task},
+    view::{today::today, week::week},
 };
 
-pub fn main_menu(list: &mut List) -> Result<()> {
+pub fn main_menu(list: &mut List, storage: &dyn Storage) -> Result<()> {
     println!("Overall Stress {:.2}", list.total_stress());
     match Select::new(
         "Select Action",
         vec![
+            "Today",
+            "Week",
             "Add Task",
             "Modify Task",
             "Complete Task",
+            "Set Status",
             "View Task",
+            "Search",
+            "Views",
+            "Tags",
             "Update Schedule",
             "Update Default Schedule",
+            "Undo",
+            "Redo",
+            "Restore Backup",
         ],
     )
     // .with_help_message("")
     .prompt()?
     {
+        "Today" => {
+            print!("{}", today(list, Local::now().naive_local()));
+        }
+        "Week" => {
+            print!("{}", week(list, select_date()));
+        }
         "Add Task" => {
-            let task = create_task();
+            let task = create_task(&list.all_tags());
             list.add_task(task);
         }
         "Modify Task" => {
@@ -36,22 +57,53 @@ pub fn main_menu(list: &mut List) -> Result<()> {
                 println!("Dependency not completed");
             }
         }
+        "Set Status" => {
+            let task = list.pick_task(|_| true);
+            if let Err(error) = list.set_task_status(task) {
+                println!("{}", error);
+            }
+        }
         "View Task" => {
             let task = list.pick_task(|_| true);
-            println!(
-                "{}Earliest Completion: {}",
-                list.tasks.get(&task).unwrap(),
-                list.schedule.earliest_complete(
-                    list.effective_time(task),
-                    list.tasks.get(&task).unwrap().start
-                )
-            );
+            list.view_task(task);
+        }
+        "Search" => {
+            search_tasks(list);
+        }
+        "Views" => {
+            views_menu(list);
+        }
+        "Tags" => {
+            print_tag_totals(list);
         }
         "Update Schedule" => {
-            list.schedule.update();
+            list.update_schedule();
         }
         "Update Default Schedule" => {
-            list.schedule.update_default();
+            list.update_default_schedule();
+        }
+        "Undo" => {
+            if !list.undo() {
+                println!("Nothing to undo");
+            }
+        }
+        "Redo" => {
+            if !list.redo() {
+                println!("Nothing to redo");
+            }
+        }
+        "Restore Backup" => {
+            let backups = storage.list_backups()?;
+            if backups.is_empty() {
+                println!("No backups");
+                return Ok(());
+            }
+            let backup = Select::new("Select Backup", backups).prompt()?;
+            let hooks = std::mem::take(&mut list.hooks);
+            *list = storage.load_backup(&backup)?;
+            list.hooks = hooks;
+            list.schedule.clean();
+            list.migrate();
         }
         _ => {
             println!("Unknown Action");
@@ -1,56 +1,240 @@
+pub mod args;
 pub mod cli;
-use dirs::config_dir;
-use std::fs::{File, create_dir_all};
-use std::io::{Read, Write};
-use std::path::PathBuf;
 
+use anyhow::{Result, anyhow};
+use args::{Args, Command, ExportFormat, ImportFormat, ImportMode, ReportFormat};
+use chrono::{Local, TimeDelta};
 use cli::main_menu;
+use itertools::Itertools;
 
-use crate::list::List;
+use crate::alerts::print_warning;
+use crate::api::{self, Session};
+use crate::config::Config;
+use crate::export::{self, write_output};
+use crate::import::{self, merge_into, preview};
+use crate::list::{List, cli::print_tag_totals};
+use crate::merge::cli::merge_files;
+use crate::query::Query;
+use crate::report::{self, ReportOptions};
+use crate::storage::{self, Storage};
+use crate::tui;
+use crate::view;
 
-fn get_datafile() -> PathBuf {
-    let mut dir = config_dir().unwrap();
-    dir.push("todo");
-    dir.push("data.yaml");
-    return dir;
+pub fn save(storage: &mut dyn Storage, list: &List) {
+    storage.save(list).unwrap();
 }
 
-pub fn save(list: &List) {
-    let mut file = if let Ok(file) = std::fs::OpenOptions::new()
-        .write(true)
-        .truncate(true)
-        .open(get_datafile())
-    {
-        file
-    } else {
-        create_dir_all(get_datafile().parent().unwrap()).unwrap();
-        File::create(get_datafile()).unwrap()
-    };
-
-    file.write_all(serde_yaml::to_string(list).unwrap().as_bytes())
-        .unwrap();
+pub fn load(storage: &mut dyn Storage) -> Option<List> {
+    let mut list = storage.load().unwrap()?;
+    list.schedule.clean();
+    if list.migrate() {
+        save(storage, &list);
+    }
+    Some(list)
 }
 
-pub fn load() -> Option<List> {
-    let mut file = if let Ok(file) = File::open(get_datafile()) {
-        file
-    } else {
-        return None;
-    };
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-
-    let mut list: List = serde_yaml::from_str(&contents).unwrap();
-    list.schedule.clean();
-    return Some(list);
+pub fn open() -> (Box<dyn Storage>, List, Config) {
+    let config = Config::load().unwrap();
+    let mut storage = storage::open(&config);
+    let mut list = load(storage.as_mut()).unwrap_or(List::new());
+    list.hooks = config.hooks.clone();
+    if list.hooks.stress_threshold.is_none() {
+        list.hooks.stress_threshold = config.alerts.task;
+    }
+    (storage, list, config)
 }
 
 pub fn spin() {
-    let mut list = load().unwrap_or(List::new());
+    let (mut storage, mut list, config) = open();
+    print_warning(&list.alerts(&config.alerts));
+    let mut stressed = list.stressed_tasks();
     loop {
-        if main_menu(&mut list).is_err() {
+        if main_menu(&mut list, storage.as_ref()).is_err() {
             break;
         };
-        save(&list);
+        list.commit();
+        stressed = list.run_stress_hooks(&stressed);
+        save(storage.as_mut(), &list);
+    }
+}
+
+pub fn run(args: Args) -> Result<()> {
+    match args.command {
+        None => spin(),
+        Some(Command::Merge {
+            base,
+            ours,
+            theirs,
+            output,
+        }) => merge_files(&base, &ours, &theirs, output.as_deref())?,
+        Some(Command::Export {
+            format,
+            columns,
+            filter,
+            output,
+        }) => {
+            let (_, list, _) = open();
+            let query = Query::parse(filter.as_deref().unwrap_or(""))?;
+            let contents = match format {
+                ExportFormat::Json => export::json::export(&list, &query)?,
+                ExportFormat::Ics => export::ics::export(&list, &query),
+                ExportFormat::Csv => export::csv::export(&list, &columns, &query),
+            };
+            write_output(output.as_deref(), &contents)?;
+        }
+        Some(Command::Import {
+            file,
+            format,
+            mode,
+            dry_run,
+            estimate_uda,
+        }) => {
+            let contents = std::fs::read_to_string(file)?;
+            let imported = match format {
+                ImportFormat::Json => import::json::import(&contents)?,
+                ImportFormat::Todotxt => import::todotxt::import(&contents)?,
+                ImportFormat::Taskwarrior => import::taskwarrior::import(&contents, &estimate_uda)?,
+            };
+            if dry_run {
+                preview(&imported);
+                return Ok(());
+            }
+            let (mut storage, mut list, _) = open();
+            match mode {
+                ImportMode::Replace => list = imported,
+                ImportMode::Merge => merge_into(&mut list, imported),
+            }
+            list.commit();
+            save(storage.as_mut(), &list);
+            println!("{} tasks in list", list.tasks.len());
+        }
+        Some(Command::Busy {
+            file,
+            days,
+            dry_run,
+        }) => {
+            let contents = std::fs::read_to_string(file)?;
+            let horizon = Local::now().naive_local() + TimeDelta::days(days);
+            let (busy, warnings) = import::ics::busy_times(&contents, horizon)?;
+            warnings.iter().for_each(|warning| println!("{}", warning));
+            for time in busy.iter() {
+                println!("{} - {} {}", time.start, time.end, time.summary);
+            }
+            if dry_run {
+                return Ok(());
+            }
+            let (mut storage, mut list, _) = open();
+            for time in busy.iter() {
+                list.mark_busy(time.start, time.end);
+            }
+            list.commit();
+            save(storage.as_mut(), &list);
+        }
+        Some(Command::Report {
+            format,
+            subtree,
+            due_within,
+            output,
+        }) => {
+            let (_, list, _) = open();
+            let options = ReportOptions {
+                subtree,
+                due_within: due_within.map(TimeDelta::days),
+            };
+            let report = report::build(&list, &options)?;
+            let contents = match format {
+                ReportFormat::Markdown => report::markdown::render(&report),
+                ReportFormat::Html => report::html::render(&report),
+            };
+            write_output(output.as_deref(), &contents)?;
+        }
+        Some(Command::Graph { subtree, output }) => {
+            let (_, list, _) = open();
+            write_output(output.as_deref(), &export::dot::export(&list, subtree)?)?;
+        }
+        Some(Command::Serve { port }) => api::http::serve(Session::open(), port)?,
+        Some(Command::Rpc) => api::rpc::serve(Session::open())?,
+        Some(Command::Tui) => tui::run()?,
+        Some(Command::Week { date }) => {
+            let (_, list, _) = open();
+            let date = date.unwrap_or(Local::now().naive_local().date());
+            print!("{}", view::week::week(&list, date));
+        }
+        Some(Command::Gantt { subtree, width }) => {
+            let (_, list, _) = open();
+            let now = Local::now().naive_local();
+            print!("{}", view::gantt::gantt(&list, subtree, now, width)?);
+        }
+        Some(Command::Search { query }) => {
+            let (_, list, _) = open();
+            for hit in list.search(&query) {
+                println!(
+                    "{} {} ({:.2})",
+                    hit.id,
+                    list.tasks.get(&hit.id).unwrap().name,
+                    list.stress(hit.id)
+                );
+            }
+        }
+        Some(Command::List { query }) => {
+            let (_, list, _) = open();
+            let query = Query::parse(&query.join(" "))?;
+            for (id, stress) in list
+                .tasks
+                .keys()
+                .filter(|&&id| query.matches(&list, id))
+                .map(|&id| (id, list.stress(id)))
+                .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+            {
+                println!(
+                    "{} {} ({:.2})",
+                    id,
+                    list.tasks.get(&id).unwrap().name,
+                    stress
+                );
+            }
+        }
+        Some(Command::View { name: None }) => {
+            let (_, list, _) = open();
+            for (name, view) in list.views.iter() {
+                println!("{}: {}", name, view);
+            }
+        }
+        Some(Command::View { name: Some(name) }) => {
+            let (_, list, _) = open();
+            let view = list
+                .views
+                .get(&name)
+                .ok_or_else(|| anyhow!("No view named \"{}\"", name))?;
+            for id in list.view_tasks(view)? {
+                let task = list.tasks.get(&id).unwrap();
+                let deadline = task
+                    .deadline
+                    .map(|deadline| format!(", due {}", report::format_datetime(deadline)))
+                    .unwrap_or_default();
+                println!("{} {} ({:.2}{})", id, task.name, list.stress(id), deadline);
+            }
+        }
+        Some(Command::Tags) => {
+            let (_, list, _) = open();
+            print_tag_totals(&list);
+        }
+        Some(Command::Today) => {
+            let (_, list, _) = open();
+            print!("{}", view::today::today(&list, Local::now().naive_local()));
+        }
+        Some(Command::Check { quiet }) => {
+            let (_, list, config) = open();
+            let alerts = list.alerts(&config.alerts);
+            if !quiet {
+                for alert in alerts.iter() {
+                    println!("{}", alert);
+                }
+            }
+            if !alerts.is_empty() {
+                std::process::exit(1);
+            }
+        }
     }
+    Ok(())
 }
@@ -0,0 +1,29 @@
+use anyhow::{Result, anyhow};
+use inquire::Select;
+use std::path::Path;
+
+use super::{Conflict, Side};
+use crate::list::List;
+use crate::storage;
+
+pub fn resolve(conflict: &Conflict) -> Side {
+    println!("{}", conflict);
+    Select::new("Keep which side?", vec![Side::Ours, Side::Theirs])
+        .prompt()
+        .unwrap()
+}
+
+fn load(path: &Path) -> Result<List> {
+    storage::open_path(path)
+        .load()?
+        .ok_or_else(|| anyhow!("{} does not exist", path.display()))
+}
+
+// Follows the git merge driver convention, the result replaces ours
+pub fn merge_files(base: &Path, ours: &Path, theirs: &Path, output: Option<&Path>) -> Result<()> {
+    let (list, report) = List::merge(&load(base)?, &load(ours)?, load(theirs)?, &mut resolve);
+    storage::open_path(output.unwrap_or(ours)).save(&list)?;
+    print!("{}", report);
+    println!("Merged {} tasks", list.tasks.len());
+    Ok(())
+}
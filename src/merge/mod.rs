@@ -0,0 +1,90 @@
+pub mod cli;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Side::Ours => write!(f, "Ours"),
+            Side::Theirs => write!(f, "Theirs"),
+        }
+    }
+}
+
+// Both sides changed the same thing in different ways
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub subject: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Conflict: {}", self.subject)?;
+        writeln!(f, "  Ours: {}", self.ours)?;
+        write!(f, "  Theirs: {}", self.theirs)
+    }
+}
+
+pub type Resolver<'a> = dyn FnMut(&Conflict) -> Side + 'a;
+
+#[derive(Debug, Default)]
+pub struct MergeReport {
+    pub conflicts: Vec<(Conflict, Side)>,
+    // Tasks from theirs that collided with a different task in ours
+    pub renumbered: Vec<(usize, usize)>,
+    pub notes: Vec<String>,
+}
+
+impl std::fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (from, to) in self.renumbered.iter() {
+            writeln!(f, "Renumbered their task {} to {}", from, to)?;
+        }
+        for (conflict, side) in self.conflicts.iter() {
+            writeln!(f, "Resolved \"{}\" with {}", conflict.subject, side)?;
+        }
+        for note in self.notes.iter() {
+            writeln!(f, "{}", note)?;
+        }
+        write!(f, "")
+    }
+}
+
+pub fn merge_value<T: PartialEq + Clone>(
+    base: Option<&T>,
+    ours: &T,
+    theirs: &T,
+    conflict: impl FnOnce() -> Conflict,
+    resolve: &mut Resolver,
+) -> T {
+    if ours == theirs || base == Some(theirs) {
+        return ours.clone();
+    }
+    if base == Some(ours) {
+        return theirs.clone();
+    }
+    match resolve(&conflict()) {
+        Side::Ours => ours.clone(),
+        Side::Theirs => theirs.clone(),
+    }
+}
+
+// Keeps whatever both sides kept and anything either side added
+pub fn merge_set<T: Eq + Hash + Clone>(
+    base: &HashSet<T>,
+    ours: &HashSet<T>,
+    theirs: &HashSet<T>,
+) -> HashSet<T> {
+    ours.union(theirs)
+        .filter(|item| (ours.contains(item) && theirs.contains(item)) || !base.contains(item))
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,95 @@
+use inquire::validator::Validation;
+use inquire::{Select, Text};
+
+use super::Query;
+use super::view::{SavedView, SortOrder};
+use crate::list::List;
+
+const NEW_VIEW: &str = "New View";
+const DELETE_VIEW: &str = "Delete View";
+
+pub fn create_view(list: &List) -> (String, SavedView) {
+    let names: Vec<String> = list.views.keys().cloned().collect();
+    let name = Text::new("View Name")
+        .with_validator(move |input: &str| {
+            let name = input.trim();
+            Ok(if name.is_empty() {
+                Validation::Invalid("The name can't be empty".into())
+            } else if name == NEW_VIEW || name == DELETE_VIEW {
+                Validation::Invalid(format!("\"{}\" is used by the menu", name).into())
+            } else if names.iter().any(|other| other == name) {
+                Validation::Invalid(format!("There's already a view called \"{}\"", name).into())
+            } else {
+                Validation::Valid
+            })
+        })
+        .prompt()
+        .unwrap()
+        .trim()
+        .to_string();
+    let filter = Text::new("Filter")
+        .with_help_message("e.g. due<7d stress>2 started !has-subtasks under:\"Thesis\" tag:work")
+        .with_validator(|input: &str| {
+            Ok(match Query::parse(input) {
+                Ok(_) => Validation::Valid,
+                Err(error) => Validation::Invalid(error.to_string().into()),
+            })
+        })
+        .prompt()
+        .unwrap();
+    let sort = Select::new("Sort By", SortOrder::ALL.to_vec())
+        .prompt()
+        .unwrap();
+    (name, SavedView { filter, sort })
+}
+
+pub fn views_menu(list: &mut List) {
+    let mut options: Vec<String> = list.views.keys().cloned().collect();
+    options.push(String::from(NEW_VIEW));
+    if !list.views.is_empty() {
+        options.push(String::from(DELETE_VIEW));
+    }
+    let choice = Select::new("Select View", options).prompt().unwrap();
+    if choice == NEW_VIEW {
+        let (name, view) = create_view(list);
+        list.set_view(name, Some(view));
+        return;
+    }
+    if choice == DELETE_VIEW {
+        let name = Select::new("Delete View", list.views.keys().cloned().collect())
+            .prompt()
+            .unwrap();
+        list.set_view(name, None);
+        return;
+    }
+
+    let view = list.views.get(&choice).unwrap();
+    let ids = match list.view_tasks(view) {
+        Ok(ids) => ids,
+        Err(error) => {
+            println!("{}", error);
+            return;
+        }
+    };
+    if ids.is_empty() {
+        println!("No tasks in this view");
+        return;
+    }
+    let labels: Vec<String> = ids
+        .iter()
+        .map(|&id| {
+            format!(
+                "{} {} ({:.2})",
+                id,
+                list.tasks.get(&id).unwrap().name,
+                list.stress(id)
+            )
+        })
+        .collect();
+    let task = Select::new("Select a Task", labels.clone())
+        .with_page_size(30)
+        .prompt()
+        .unwrap();
+    let id = ids[labels.iter().position(|label| *label == task).unwrap()];
+    list.task_action(id);
+}
@@ -0,0 +1,160 @@
+pub mod cli;
+mod parse;
+pub mod view;
+use chrono::{Local, NaiveDateTime, TimeDelta};
+
+use crate::list::List;
+use crate::task::{Priority, Status};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+impl Comparison {
+    fn compare<T: PartialOrd>(&self, a: T, b: T) -> bool {
+        match self {
+            Comparison::Lt => a < b,
+            Comparison::Le => a <= b,
+            Comparison::Gt => a > b,
+            Comparison::Ge => a >= b,
+            Comparison::Eq => a == b,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Target {
+    Id(usize),
+    // Case insensitive
+    Name(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Term {
+    // Deadline compared to now plus the duration
+    Due(Comparison, TimeDelta),
+    Stress(Comparison, f32),
+    Priority(Comparison, Priority),
+    Status(Status),
+    // Remaining hours including subtasks
+    Hours(Comparison, f32),
+    Started,
+    // Has subtasks left to complete
+    HasSubtasks,
+    // Can't be finished by its deadline with the current schedule
+    Late,
+    Overdue,
+    Root,
+    // Somewhere below the target, not the target itself
+    Under(Target),
+    // Case insensitive substring of the name
+    Name(String),
+    Tag(String),
+    Not(Box<Term>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub query: String,
+    // Character the error points at
+    pub position: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(query: &str, position: usize, message: &str) -> Self {
+        ParseError {
+            query: query.to_string(),
+            position,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "  {}", self.query)?;
+        write!(f, "  {}^", " ".repeat(self.position))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Space separated terms that all have to match, e.g.
+// due<7d stress>2 started !has-subtasks under:"Thesis" tag:work
+#[derive(Debug, Clone)]
+pub struct Query {
+    terms: Vec<Term>,
+    now: NaiveDateTime,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Self, ParseError> {
+        Ok(Query {
+            terms: parse::parse(query)?,
+            now: Local::now().naive_local(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    fn term_matches(&self, term: &Term, list: &List, id: usize) -> bool {
+        let task = list.tasks.get(&id).unwrap();
+        match term {
+            Term::Due(comparison, duration) => task.deadline.is_some_and(|deadline| {
+                match self.now.checked_add_signed(*duration) {
+                    Some(limit) => comparison.compare(deadline, limit),
+                    // Off the end of the calendar, so past every deadline
+                    None if *duration > TimeDelta::zero() => {
+                        matches!(comparison, Comparison::Lt | Comparison::Le)
+                    }
+                    None => matches!(comparison, Comparison::Gt | Comparison::Ge),
+                }
+            }),
+            Term::Stress(comparison, stress) => comparison.compare(list.stress(id), *stress),
+            Term::Priority(comparison, priority) => comparison.compare(task.priority, *priority),
+            Term::Hours(comparison, hours) => {
+                comparison.compare(list.effective_time(id).as_seconds_f32() / 3600.0, *hours)
+            }
+            Term::Started => task.started(),
+            Term::HasSubtasks => !task.subtasks.is_empty(),
+            Term::Late => task.deadline.is_some_and(|deadline| {
+                list.schedule
+                    .earliest_complete(list.effective_time(id), task.start)
+                    .is_none_or(|earliest| earliest > deadline)
+            }),
+            Term::Overdue => task.deadline.is_some_and(|deadline| deadline < self.now),
+            Term::Root => task.supertasks.is_empty(),
+            Term::Under(target) => list.get_all_parents(id).iter().any(|&parent| {
+                parent != id
+                    && match target {
+                        Target::Id(target) => parent == *target,
+                        Target::Name(name) => list
+                            .tasks
+                            .get(&parent)
+                            .unwrap()
+                            .name
+                            .eq_ignore_ascii_case(name),
+                    }
+            }),
+            Term::Name(name) => task.name.to_lowercase().contains(&name.to_lowercase()),
+            Term::Tag(tag) => task.tags.contains(tag),
+            Term::Status(status) => task.status == *status,
+            Term::Not(term) => !self.term_matches(term, list, id),
+        }
+    }
+
+    pub fn matches(&self, list: &List, id: usize) -> bool {
+        self.terms
+            .iter()
+            .all(|term| self.term_matches(term, list, id))
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::TimeDelta;
+
+use super::{Comparison, ParseError, Target, Term};
+use crate::task::Status;
+
+struct Token {
+    // Column the token starts at, for pointing at errors
+    position: usize,
+    text: String,
+}
+
+// Splits on whitespace, double quotes keep spaces in a value
+fn tokenize(query: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut current: Option<Token> = None;
+    let mut quote_start = None;
+    for (position, c) in query.chars().enumerate() {
+        match c {
+            '"' => {
+                quote_start = match quote_start {
+                    Some(_) => None,
+                    None => Some(position),
+                };
+                current
+                    .get_or_insert(Token {
+                        position,
+                        text: String::new(),
+                    })
+                    .text
+                    .push(c);
+            }
+            c if c.is_whitespace() && quote_start.is_none() => {
+                tokens.extend(current.take());
+            }
+            c => current
+                .get_or_insert(Token {
+                    position,
+                    text: String::new(),
+                })
+                .text
+                .push(c),
+        }
+    }
+    if let Some(position) = quote_start {
+        return Err(ParseError::new(query, position, "Unclosed quote"));
+    }
+    tokens.extend(current);
+    Ok(tokens)
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+fn duration(value: &str) -> Option<TimeDelta> {
+    let unit = value.chars().last()?;
+    let amount: f64 = value[..value.len() - unit.len_utf8()].parse().ok()?;
+    let minutes = amount
+        * match unit {
+            'm' => 1.0,
+            'h' => 60.0,
+            'd' => 24.0 * 60.0,
+            'w' => 7.0 * 24.0 * 60.0,
+            _ => return None,
+        };
+    TimeDelta::try_minutes(minutes.round() as i64)
+}
+
+fn term(query: &str, token: &Token) -> Result<Term, ParseError> {
+    let error = |offset: usize, message: String| {
+        Err(ParseError::new(query, token.position + offset, &message))
+    };
+    if let Some(rest) = token.text.strip_prefix('!') {
+        let inner = Token {
+            position: token.position + 1,
+            text: rest.to_string(),
+        };
+        return Ok(Term::Not(Box::new(term(query, &inner)?)));
+    }
+
+    let key_end = token
+        .text
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '-')
+        .unwrap_or(token.text.len());
+    let key = &token.text[..key_end];
+    let rest = &token.text[key_end..];
+    if key.is_empty() {
+        return error(
+            0,
+            format!("Expected a filter name, found \"{}\"", token.text),
+        );
+    }
+    if rest.is_empty() {
+        return match key {
+            "started" => Ok(Term::Started),
+            "has-subtasks" => Ok(Term::HasSubtasks),
+            // Used to mean has-subtasks before blocked became a status
+            "blocked" => error(
+                0,
+                String::from("Use status:blocked, or has-subtasks for tasks with subtasks left"),
+            ),
+            "late" => Ok(Term::Late),
+            "overdue" => Ok(Term::Overdue),
+            "root" => Ok(Term::Root),
+            _ => error(0, format!("Unknown filter \"{}\"", key)),
+        };
+    }
+
+    let (operator, value) = if let Some(value) = rest.strip_prefix(':') {
+        (None, value)
+    } else {
+        let (comparison, length) = if rest.starts_with("<=") {
+            (Comparison::Le, 2)
+        } else if rest.starts_with(">=") {
+            (Comparison::Ge, 2)
+        } else if rest.starts_with('<') {
+            (Comparison::Lt, 1)
+        } else if rest.starts_with('>') {
+            (Comparison::Gt, 1)
+        } else if rest.starts_with('=') {
+            (Comparison::Eq, 1)
+        } else {
+            return error(key_end, String::from("Expected one of : < <= > >= ="));
+        };
+        (Some(comparison), &rest[length..])
+    };
+    let value_offset = token.text.len() - value.len();
+    if value.is_empty() {
+        return error(value_offset, format!("Missing value for \"{}\"", key));
+    }
+
+    match (key, operator) {
+        ("due", Some(comparison)) => match duration(value) {
+            Some(duration) => Ok(Term::Due(comparison, duration)),
+            None => error(
+                value_offset,
+                format!("Expected a duration like 3h, 7d or 2w, found \"{}\"", value),
+            ),
+        },
+        ("stress" | "hours", Some(comparison)) => match value.parse() {
+            Ok(number) if key == "stress" => Ok(Term::Stress(comparison, number)),
+            Ok(number) => Ok(Term::Hours(comparison, number)),
+            Err(_) => error(
+                value_offset,
+                format!("Expected a number, found \"{}\"", value),
+            ),
+        },
+        ("priority", comparison) => match value.parse() {
+            Ok(priority) => Ok(Term::Priority(
+                comparison.unwrap_or(Comparison::Eq),
+                priority,
+            )),
+            Err(_) => error(
+                value_offset,
+                format!("Expected low, normal, high or urgent, found \"{}\"", value),
+            ),
+        },
+        // Closed tasks are gone from the list, so they'd never match
+        ("status", None) => match value.parse::<Status>() {
+            Ok(status) if !status.is_closed() => Ok(Term::Status(status)),
+            _ => error(
+                value_offset,
+                format!(
+                    "Expected todo, in-progress, waiting or blocked, found \"{}\"",
+                    value
+                ),
+            ),
+        },
+        ("under", None) => {
+            let value = unquote(value);
+            Ok(Term::Under(match value.parse() {
+                Ok(id) => Target::Id(id),
+                Err(_) => Target::Name(value),
+            }))
+        }
+        ("name", None) => Ok(Term::Name(unquote(value))),
+        ("tag", None) => Ok(Term::Tag(unquote(value).to_lowercase())),
+        ("due" | "stress" | "hours", None) => error(
+            key_end,
+            format!("\"{}\" needs a comparison like {}<3", key, key),
+        ),
+        ("under" | "name" | "tag" | "status", Some(_)) => {
+            error(key_end, format!("Use {}:value", key))
+        }
+        _ => error(0, format!("Unknown filter \"{}\"", key)),
+    }
+}
+
+pub fn parse(query: &str) -> Result<Vec<Term>, ParseError> {
+    tokenize(query)?
+        .iter()
+        .map(|token| term(query, token))
+        .collect()
+}
@@ -0,0 +1,116 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+use super::{ParseError, Query};
+use crate::list::List;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    // Most stressful first
+    #[default]
+    Stress,
+    // Soonest first, tasks without one last
+    Deadline,
+    // Soonest earliest completion first
+    Earliest,
+    // Least remaining work first
+    Estimate,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 4] = [
+        SortOrder::Stress,
+        SortOrder::Deadline,
+        SortOrder::Earliest,
+        SortOrder::Estimate,
+    ];
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Stress => write!(f, "Stress"),
+            SortOrder::Deadline => write!(f, "Deadline"),
+            SortOrder::Earliest => write!(f, "Earliest Completion"),
+            SortOrder::Estimate => write!(f, "Estimate"),
+        }
+    }
+}
+
+// A named filter saved with the list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedView {
+    // In the query language, e.g. due<7d started
+    pub filter: String,
+    #[serde(default)]
+    pub sort: SortOrder,
+}
+
+impl SavedView {
+    // Bare blocked meant has-subtasks before blocked became a status, true if
+    // the filter changed
+    pub fn migrate(&mut self) -> bool {
+        let filter = self
+            .filter
+            .split(' ')
+            .map(|token| match token {
+                "blocked" => "has-subtasks",
+                "!blocked" => "!has-subtasks",
+                token => token,
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        if filter == self.filter {
+            return false;
+        }
+        self.filter = filter;
+        true
+    }
+}
+
+impl std::fmt::Display for SavedView {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (by {})", self.filter, self.sort)
+    }
+}
+
+// Missing values sort after everything else
+fn soonest<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl List {
+    pub fn view_tasks(&self, view: &SavedView) -> Result<Vec<usize>, ParseError> {
+        let query = Query::parse(&view.filter)?;
+        let earliest = |id: usize| {
+            let task = self.tasks.get(&id).unwrap();
+            self.schedule
+                .earliest_complete(self.effective_time(id), task.start)
+        };
+        Ok(self
+            .tasks
+            .keys()
+            .cloned()
+            .filter(|&id| query.matches(self, id))
+            .sorted_by(|&a, &b| {
+                let order = match view.sort {
+                    SortOrder::Stress => self.stress(b).total_cmp(&self.stress(a)),
+                    SortOrder::Deadline => soonest(
+                        self.tasks.get(&a).unwrap().deadline,
+                        self.tasks.get(&b).unwrap().deadline,
+                    ),
+                    SortOrder::Earliest => soonest(earliest(a), earliest(b)),
+                    SortOrder::Estimate => self.effective_time(a).cmp(&self.effective_time(b)),
+                };
+                order.then(a.cmp(&b))
+            })
+            .collect())
+    }
+}
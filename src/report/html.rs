@@ -0,0 +1,70 @@
+use super::{Node, Report, format_datetime};
+
+const STYLE: &str = "
+body { font-family: sans-serif; max-width: 60rem; margin: 2rem auto; color: #222; }
+ul { list-style: none; padding-left: 1.5rem; }
+li { margin: 0.3rem 0; }
+.name { font-weight: bold; }
+.meta { color: #555; font-size: 0.9rem; margin-left: 0.5rem; }
+.late { color: #b00; font-weight: bold; margin-left: 0.5rem; }
+progress { width: 6rem; vertical-align: middle; margin-left: 0.5rem; }
+";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_node(node: &Node, out: &mut String) {
+    let mut meta = format!("#{} stress {:.2}, {:.1}h", node.id, node.stress, node.hours);
+    if let Some(deadline) = node.deadline {
+        meta.push_str(&format!(", due {}", format_datetime(deadline)));
+    }
+    match node.earliest {
+        Some(earliest) => meta.push_str(&format!(", earliest {}", format_datetime(earliest))),
+        None => meta.push_str(", not enough scheduled time"),
+    }
+    out.push_str(&format!(
+        "<li><span class=\"name\">{}</span><span class=\"meta\">{}</span>\
+         <progress value=\"{:.2}\" max=\"1\"></progress> {:.0}%",
+        escape(&node.name),
+        meta,
+        node.progress,
+        node.progress * 100.0
+    ));
+    if node.late() {
+        out.push_str("<span class=\"late\">late</span>");
+    }
+    if !node.children.is_empty() {
+        out.push_str("\n<ul>\n");
+        for child in node.children.iter() {
+            render_node(child, out);
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</li>\n");
+}
+
+// A single file with the styles inlined so it can be mailed or attached
+pub fn render(report: &Report) -> String {
+    let mut out = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<title>Task Report</title>\n");
+    out.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", STYLE));
+    out.push_str("<h1>Task Report</h1>\n");
+    out.push_str(&format!(
+        "<p>Generated {}, overall stress {:.2}</p>\n",
+        format_datetime(report.generated),
+        report.total_stress
+    ));
+    if report.roots.is_empty() {
+        out.push_str("<p>No tasks</p>\n");
+    }
+    out.push_str("<ul>\n");
+    for root in report.roots.iter() {
+        render_node(root, &mut out);
+    }
+    out.push_str("</ul>\n</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,43 @@
+use super::{Node, Report, format_datetime};
+
+fn line(node: &Node) -> String {
+    let mut line = format!(
+        "**{}** (#{}) stress {:.2}, {:.1}h",
+        node.name, node.id, node.stress, node.hours
+    );
+    if let Some(deadline) = node.deadline {
+        line.push_str(&format!(", due {}", format_datetime(deadline)));
+    }
+    match node.earliest {
+        Some(earliest) => line.push_str(&format!(", earliest {}", format_datetime(earliest))),
+        None => line.push_str(", not enough scheduled time"),
+    }
+    line.push_str(&format!(", {:.0}% done", node.progress * 100.0));
+    if node.late() {
+        line.push_str(" **late**");
+    }
+    line
+}
+
+fn render_node(node: &Node, depth: usize, out: &mut String) {
+    out.push_str(&format!("{}- {}\n", "  ".repeat(depth), line(node)));
+    for child in node.children.iter() {
+        render_node(child, depth + 1, out);
+    }
+}
+
+pub fn render(report: &Report) -> String {
+    let mut out = String::from("# Task Report\n\n");
+    out.push_str(&format!(
+        "Generated {}, overall stress {:.2}\n\n",
+        format_datetime(report.generated),
+        report.total_stress
+    ));
+    if report.roots.is_empty() {
+        out.push_str("No tasks\n");
+    }
+    for root in report.roots.iter() {
+        render_node(root, 0, &mut out);
+    }
+    out
+}
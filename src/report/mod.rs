@@ -0,0 +1,114 @@
+pub mod html;
+pub mod markdown;
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use itertools::Itertools;
+
+use crate::list::List;
+
+#[derive(Debug, Clone, Default)]
+pub struct ReportOptions {
+    // Only this task and what is under it
+    pub subtree: Option<usize>,
+    // Only tasks due before now + window, along with their supertasks
+    pub due_within: Option<TimeDelta>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub id: usize,
+    pub name: String,
+    pub stress: f32,
+    pub hours: f32,
+    pub deadline: Option<NaiveDateTime>,
+    pub earliest: Option<NaiveDateTime>,
+    // Share of the estimated time already completed, 0 to 1
+    pub progress: f32,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn late(&self) -> bool {
+        match (self.deadline, self.earliest) {
+            (Some(deadline), Some(earliest)) => earliest > deadline,
+            (Some(_), None) => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub generated: NaiveDateTime,
+    pub total_stress: f32,
+    pub roots: Vec<Node>,
+}
+
+fn hours(time: TimeDelta) -> f32 {
+    time.as_seconds_f32() / 3600.0
+}
+
+fn node(list: &List, id: usize, due_before: Option<NaiveDateTime>) -> Option<Node> {
+    let task = list.tasks.get(&id).unwrap();
+    let children: Vec<Node> = task
+        .subtasks
+        .iter()
+        .filter_map(|&subtask| node(list, subtask, due_before))
+        .sorted_by(|a, b| b.stress.partial_cmp(&a.stress).unwrap())
+        .collect();
+    let due = match (due_before, task.deadline) {
+        (None, _) => true,
+        (Some(due_before), Some(deadline)) => deadline <= due_before,
+        (Some(_), None) => false,
+    };
+    if !due && children.is_empty() {
+        return None;
+    }
+    let remaining = list.effective_time(id);
+    let completed = list.completed_time(id);
+    let total = hours(remaining + completed);
+    Some(Node {
+        id,
+        name: task.name.clone(),
+        stress: list.stress(id),
+        hours: hours(remaining),
+        deadline: task.deadline,
+        earliest: list.schedule.earliest_complete(remaining, task.start),
+        progress: if total > 0.0 {
+            hours(completed) / total
+        } else {
+            0.0
+        },
+        children,
+    })
+}
+
+pub fn build(list: &List, options: &ReportOptions) -> Result<Report> {
+    let now = Local::now().naive_local();
+    let roots: Vec<usize> = if let Some(subtree) = options.subtree {
+        if !list.tasks.contains_key(&subtree) {
+            return Err(anyhow!("No task with id {}", subtree));
+        }
+        vec![subtree]
+    } else {
+        list.tasks
+            .values()
+            .filter(|task| task.supertasks.is_empty())
+            .map(|task| task.id)
+            .collect()
+    };
+    let due_before = options.due_within.map(|window| now + window);
+    Ok(Report {
+        generated: now,
+        total_stress: list.total_stress(),
+        roots: roots
+            .into_iter()
+            .filter_map(|id| node(list, id, due_before))
+            .sorted_by(|a, b| b.stress.partial_cmp(&a.stress).unwrap())
+            .collect(),
+    })
+}
+
+pub fn format_datetime(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y-%m-%d %H:%M").to_string()
+}
@@ -1,4 +1,4 @@
-use super::{Itinerary, Schedule, TimeBlock};
+use super::{Itinerary, TimeBlock};
 use crate::task::cli::get_time;
 use chrono::{NaiveDate, Weekday};
 use inquire::{DateSelect, Select};
@@ -95,24 +95,7 @@ impl Itinerary {
     }
 }
 
-impl Schedule {
-    pub fn update(&mut self) {
-        let date = select_date();
-        if let Some(itinerary) = self.schedule.get_mut(&date) {
-            itinerary.update();
-            return;
-        }
-        let itinerary = create_itinerary();
-        self.schedule.insert(date, itinerary);
-    }
-
-    pub fn update_default(&mut self) {
-        let day = select_day();
-        self.default_schedule.get_mut_itinerary(day).update();
-    }
-}
-
-fn select_day() -> Weekday {
+pub fn select_day() -> Weekday {
     Select::new(
         "Select Action",
         vec![
@@ -129,7 +112,7 @@ fn select_day() -> Weekday {
     .unwrap()
 }
 
-fn select_date() -> NaiveDate {
+pub fn select_date() -> NaiveDate {
     DateSelect::new("Select Date to Schedule")
         .with_help_message("Enter a date")
         .prompt()
@@ -5,7 +5,7 @@ use chrono::{NaiveTime, TimeDelta, Timelike};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Itinerary {
     pub timeblocks: HashSet<TimeBlock>,
 }
@@ -48,6 +48,15 @@ impl Itinerary {
         time
     }
 
+    // Removes the time between start and end, splitting blocks around it
+    pub fn subtract(&mut self, start: NaiveTime, end: NaiveTime) {
+        self.timeblocks = self
+            .timeblocks
+            .iter()
+            .flat_map(|block| block.subtract(start, end))
+            .collect();
+    }
+
     fn overlaps(&self, other_block: &TimeBlock) -> bool {
         for block in self.timeblocks.iter() {
             if block.overlaps(other_block) {
@@ -101,6 +110,10 @@ impl TimeBlock {
         } else {
             self.start
         };
+        if new_start >= self.end {
+            // Block is already over
+            return (start, time);
+        }
         let duration = self.end.signed_duration_since(new_start);
         let new_time = time.checked_sub(&duration).unwrap();
         if new_time > TimeDelta::zero() {
@@ -124,6 +137,20 @@ impl TimeBlock {
         return new_end.signed_duration_since(new_start);
     }
 
+    pub fn subtract(&self, start: NaiveTime, end: NaiveTime) -> Vec<TimeBlock> {
+        if end <= self.start || start >= self.end {
+            return vec![*self];
+        }
+        let mut blocks = Vec::new();
+        if start > self.start {
+            blocks.push(TimeBlock::from_start_end(self.start, start));
+        }
+        if end < self.end {
+            blocks.push(TimeBlock::from_start_end(end, self.end));
+        }
+        blocks
+    }
+
     fn overlaps(&self, other_block: &TimeBlock) -> bool {
         if self.start >= other_block.start && self.start < other_block.end {
             return true;
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use itertools::Itertools;
+
+use super::{Itinerary, Schedule, WEEK};
+use crate::merge::{Conflict, Resolver, Side, merge_set};
+
+fn show(itinerary: Option<&Itinerary>) -> String {
+    if let Some(itinerary) = itinerary {
+        return itinerary
+            .timeblocks
+            .iter()
+            .sorted()
+            .map(|block| block.to_string().trim().to_string())
+            .join(", ");
+    }
+    String::from("(removed)")
+}
+
+fn merge_itinerary(
+    subject: String,
+    base: Option<&Itinerary>,
+    ours: Option<&Itinerary>,
+    theirs: Option<&Itinerary>,
+    resolve: &mut Resolver,
+) -> Option<Itinerary> {
+    if ours == theirs || base == theirs {
+        return ours.cloned();
+    }
+    if base == ours {
+        return theirs.cloned();
+    }
+    if let (Some(our_blocks), Some(their_blocks)) = (ours, theirs) {
+        let empty = HashSet::new();
+        let base_blocks = base.map(|base| &base.timeblocks).unwrap_or(&empty);
+        let mut merged = Itinerary::new();
+        let fits = merge_set(
+            base_blocks,
+            &our_blocks.timeblocks,
+            &their_blocks.timeblocks,
+        )
+        .into_iter()
+        .sorted()
+        .all(|block| merged.add_timeblock(block).is_ok());
+        if fits {
+            return Some(merged);
+        }
+    }
+    let conflict = Conflict {
+        subject,
+        ours: show(ours),
+        theirs: show(theirs),
+    };
+    match resolve(&conflict) {
+        Side::Ours => ours.cloned(),
+        Side::Theirs => theirs.cloned(),
+    }
+}
+
+impl Schedule {
+    pub fn merge(
+        base: &Schedule,
+        ours: &Schedule,
+        theirs: &Schedule,
+        resolve: &mut Resolver,
+    ) -> Schedule {
+        let mut schedule = Schedule::new();
+        let dates: Vec<NaiveDate> = base
+            .schedule
+            .keys()
+            .chain(ours.schedule.keys())
+            .chain(theirs.schedule.keys())
+            .cloned()
+            .unique()
+            .sorted()
+            .collect();
+        for date in dates {
+            let itinerary = merge_itinerary(
+                format!("Schedule for {}", date),
+                base.schedule.get(&date),
+                ours.schedule.get(&date),
+                theirs.schedule.get(&date),
+                resolve,
+            );
+            if let Some(itinerary) = itinerary {
+                schedule.set_itinerary(date, itinerary);
+            }
+        }
+        for weekday in WEEK {
+            let itinerary = merge_itinerary(
+                format!("Default schedule for {}", weekday),
+                Some(base.get_default_itinerary(weekday)),
+                Some(ours.get_default_itinerary(weekday)),
+                Some(theirs.get_default_itinerary(weekday)),
+                resolve,
+            );
+            schedule.set_default_itinerary(weekday, itinerary.unwrap_or_else(Itinerary::new));
+        }
+        schedule
+    }
+}
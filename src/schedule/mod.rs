@@ -1,12 +1,34 @@
 pub mod cli;
 mod default_schedule;
 mod itinerary;
+mod merge;
 use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
 use default_schedule::DefaultSchedule;
+use itertools::Itertools;
 pub use itinerary::{Itinerary, TimeBlock};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+const PLANNING_DAYS: usize = 5 * 365;
+
+pub const WEEK: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+// Scheduled time set aside for one task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub id: usize,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Schedule {
     schedule: HashMap<NaiveDate, Itinerary>,
@@ -21,26 +43,73 @@ impl Schedule {
         }
     }
 
+    // None if the schedule doesn't have enough time within the next few years
     pub fn earliest_complete(
         &self,
         mut time: TimeDelta,
         task_start: Option<NaiveDateTime>,
-    ) -> NaiveDateTime {
-        println!("SCHEDULE");
+    ) -> Option<NaiveDateTime> {
         let mut start = Local::now().naive_local();
         if let Some(task_start) = task_start {
             start = start.max(task_start);
         }
         let mut start_time = start.time();
-        for date in start.date().iter_days() {
+        for date in start.date().iter_days().take(PLANNING_DAYS) {
             let itinerary = self.get_itinerary(date);
             (start_time, time) = itinerary.earliest_complete(start_time, time);
             if time == TimeDelta::zero() {
-                return date.and_time(start_time);
+                return Some(date.and_time(start_time));
             }
             start_time = NaiveTime::MIN;
         }
-        unreachable!("Task not completable?");
+        None
+    }
+
+    // Fills the free scheduled time with the work in the order given, each
+    // piece no earlier than its own start. Time handed to one task is gone for
+    // the ones after it.
+    pub fn plan(
+        &self,
+        now: NaiveDateTime,
+        work: &[(usize, Option<NaiveDateTime>, TimeDelta)],
+    ) -> Vec<Slot> {
+        let mut free: HashMap<NaiveDate, Itinerary> = HashMap::new();
+        let mut slots = Vec::new();
+        for &(id, start, mut time) in work {
+            let start = start.map_or(now, |start| start.max(now));
+            let mut start_time = start.time();
+            for date in start.date().iter_days().take(PLANNING_DAYS) {
+                if time <= TimeDelta::zero() {
+                    break;
+                }
+                let itinerary = free
+                    .entry(date)
+                    .or_insert_with(|| self.get_itinerary(date).clone());
+                let mut used = Vec::new();
+                for block in itinerary.timeblocks.iter().sorted() {
+                    let begin = block.start.max(start_time);
+                    if begin >= block.end {
+                        continue;
+                    }
+                    let length = (block.end - begin).min(time);
+                    used.push((begin, begin + length));
+                    time -= length;
+                    if time <= TimeDelta::zero() {
+                        break;
+                    }
+                }
+                for &(begin, end) in used.iter() {
+                    itinerary.subtract(begin, end);
+                    slots.push(Slot {
+                        id,
+                        start: date.and_time(begin),
+                        end: date.and_time(end),
+                    });
+                }
+                start_time = NaiveTime::MIN;
+            }
+        }
+        slots
     }
 
     pub fn time_until(&self, datetime: NaiveDateTime) -> TimeDelta {
@@ -81,10 +150,31 @@ impl Schedule {
         return self.default_schedule.get_mut_itinerary(date.weekday());
     }
 
+    // Only the itinerary planned for this exact date, ignoring the default
+    pub fn get_scheduled_itinerary(&self, date: NaiveDate) -> Option<&Itinerary> {
+        self.schedule.get(&date)
+    }
+
+    pub fn scheduled_itineraries(&self) -> Vec<(NaiveDate, &Itinerary)> {
+        self.schedule
+            .iter()
+            .map(|(&date, itinerary)| (date, itinerary))
+            .sorted_by_key(|(date, _)| *date)
+            .collect()
+    }
+
+    pub fn get_default_itinerary(&self, weekday: Weekday) -> &Itinerary {
+        self.default_schedule.get_itinerary(weekday)
+    }
+
     pub fn set_itinerary(&mut self, date: NaiveDate, itinerary: Itinerary) {
         self.schedule.insert(date, itinerary);
     }
 
+    pub fn remove_itinerary(&mut self, date: NaiveDate) {
+        self.schedule.remove(&date);
+    }
+
     pub fn set_default_itinerary(&mut self, weekday: Weekday, itinerary: Itinerary) {
         self.default_schedule.set_itinerary(weekday, itinerary);
     }
@@ -0,0 +1,30 @@
+use inquire::{Select, Text};
+
+use crate::list::List;
+
+pub fn search_tasks(list: &mut List) {
+    let query = Text::new("Search").prompt().unwrap();
+    let hits = list.search(&query);
+    if hits.is_empty() {
+        println!("No matching tasks");
+        return;
+    }
+    let labels: Vec<String> = hits
+        .iter()
+        .map(|hit| {
+            format!(
+                "{} {} ({:.2}) [{}]",
+                hit.id,
+                list.tasks.get(&hit.id).unwrap().name,
+                list.stress(hit.id),
+                hit.field
+            )
+        })
+        .collect();
+    let choice = Select::new("Select a Task", labels.clone())
+        .with_page_size(30)
+        .prompt()
+        .unwrap();
+    let id = hits[labels.iter().position(|label| *label == choice).unwrap()].id;
+    list.task_action(id);
+}
@@ -0,0 +1,47 @@
+pub mod cli;
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use itertools::Itertools;
+
+use crate::list::List;
+
+// A name match is worth more than the same match in the description
+const NAME_WEIGHT: i64 = 2;
+
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub id: usize,
+    pub score: i64,
+    // Where the best match was
+    pub field: &'static str,
+}
+
+impl List {
+    // Tasks matching the query, best first
+    pub fn search(&self, query: &str) -> Vec<Hit> {
+        let matcher = SkimMatcherV2::default().ignore_case();
+        self.tasks
+            .values()
+            .filter_map(|task| {
+                let fields = [
+                    ("name", &task.name, NAME_WEIGHT),
+                    ("description", &task.description, 1),
+                ];
+                let tags = task.tags.iter().join(" ");
+                fields
+                    .into_iter()
+                    .chain([("tags", &tags, NAME_WEIGHT)])
+                    .filter_map(|(field, text, weight)| {
+                        let score = matcher.fuzzy_match(text, query)?;
+                        Some(Hit {
+                            id: task.id,
+                            score: score * weight,
+                            field,
+                        })
+                    })
+                    .max_by_key(|hit| hit.score)
+            })
+            .sorted_by(|a, b| b.score.cmp(&a.score).then(a.id.cmp(&b.id)))
+            .collect()
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDateTime};
+use std::fs::{File, create_dir_all, read_dir};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use super::Backup;
+
+// Microseconds, saves can come more than once a second
+const BACKUP_FORMAT: &str = "%Y%m%dT%H%M%S%6f";
+// Backups made before the microseconds were added
+const OLD_BACKUP_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+fn backup_dir(path: &Path) -> PathBuf {
+    let mut dir = path.parent().unwrap().to_path_buf();
+    dir.push("backups");
+    dir
+}
+
+fn backup_name(path: &Path, created: NaiveDateTime) -> String {
+    let stem = path.file_stem().unwrap().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    format!("{}-{}.{}", stem, created.format(BACKUP_FORMAT), extension)
+}
+
+pub fn read(path: &Path) -> Result<Option<String>> {
+    let mut file = if let Ok(file) = File::open(path) {
+        file
+    } else {
+        return Ok(None);
+    };
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(Some(contents))
+}
+
+pub fn write(path: &Path, contents: &str, backups: usize) -> Result<()> {
+    create_dir_all(path.parent().unwrap())?;
+    if backups > 0 && path.exists() {
+        let mut backup = backup_dir(path);
+        create_dir_all(&backup)?;
+        backup.push(backup_name(path, Local::now().naive_local()));
+        std::fs::copy(path, backup)?;
+        prune(path, backups)?;
+    }
+    // Written next to the data file and renamed over it, so a crash part way
+    // through never leaves a truncated data file
+    let mut temporary = path.to_path_buf();
+    temporary.set_file_name(format!(
+        ".{}.tmp",
+        path.file_name().unwrap().to_string_lossy()
+    ));
+    let mut file = File::create(&temporary)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+fn prune(path: &Path, keep: usize) -> Result<()> {
+    let backups = list_backups(path)?;
+    for backup in backups.iter().skip(keep) {
+        std::fs::remove_file(backup_dir(path).join(&backup.key))?;
+    }
+    Ok(())
+}
+
+// Newest first
+pub fn list_backups(path: &Path) -> Result<Vec<Backup>> {
+    let dir = backup_dir(path);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let stem = path.file_stem().unwrap().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    let mut backups: Vec<Backup> = read_dir(dir)?
+        .filter_map(|entry| {
+            let key = entry.ok()?.file_name().to_string_lossy().to_string();
+            let created = key
+                .strip_prefix(&format!("{}-", stem))?
+                .strip_suffix(&format!(".{}", extension))?;
+            let created = NaiveDateTime::parse_from_str(created, BACKUP_FORMAT)
+                .or_else(|_| NaiveDateTime::parse_from_str(created, OLD_BACKUP_FORMAT))
+                .ok()?;
+            Some(Backup { created, key })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created));
+    Ok(backups)
+}
+
+pub fn read_backup(path: &Path, backup: &Backup) -> Result<String> {
+    read(&backup_dir(path).join(&backup.key))?
+        .ok_or_else(|| anyhow!("Backup \"{}\" is missing", backup.key))
+}
@@ -0,0 +1,107 @@
+use anyhow::{Result, anyhow};
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs::{OpenOptions, create_dir_all};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::{Backup, Storage, file};
+use crate::list::List;
+
+// Every save appends a full snapshot as one json line, the newest line is the
+// current state and every line before it is a backup. Once there are more
+// backups than configured the file is rewritten with only the newest lines.
+pub struct JournalStorage {
+    path: PathBuf,
+    backups: usize,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    saved: NaiveDateTime,
+    list: List,
+}
+
+#[derive(Serialize)]
+struct NewEntry<'a> {
+    saved: NaiveDateTime,
+    list: &'a List,
+}
+
+impl JournalStorage {
+    pub fn new(path: PathBuf, backups: usize) -> Self {
+        JournalStorage { path, backups }
+    }
+
+    fn lines(&self) -> Result<Vec<String>> {
+        let contents = file::read(&self.path)?.unwrap_or_default();
+        Ok(contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(String::from)
+            .collect())
+    }
+}
+
+impl Storage for JournalStorage {
+    fn load(&self) -> Result<Option<List>> {
+        if let Some(line) = self.lines()?.pop() {
+            let entry: Entry = serde_json::from_str(&line)?;
+            return Ok(Some(entry.list));
+        }
+        Ok(None)
+    }
+
+    fn save(&mut self, list: &List) -> Result<()> {
+        create_dir_all(self.path.parent().unwrap())?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let entry = NewEntry {
+            saved: Local::now().naive_local(),
+            list,
+        };
+        writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+        drop(file);
+
+        let lines = self.lines()?;
+        if lines.len() > self.backups + 1 {
+            let mut contents = lines[lines.len() - self.backups - 1..].join("\n");
+            contents.push('\n');
+            file::write(&self.path, &contents, 0)?;
+        }
+        Ok(())
+    }
+
+    fn list_backups(&self) -> Result<Vec<Backup>> {
+        let mut lines = self.lines()?;
+        lines.pop();
+        let mut backups = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let entry: Entry = serde_json::from_str(line)?;
+            backups.push(Backup {
+                created: entry.saved,
+                key: i.to_string(),
+            });
+        }
+        backups.reverse();
+        Ok(backups)
+    }
+
+    fn load_backup(&self, backup: &Backup) -> Result<List> {
+        let lines = self.lines()?;
+        let line = backup
+            .key
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| lines.get(i))
+            .ok_or_else(|| anyhow!("Journal entry {} is missing", backup.key))?;
+        let entry: Entry = serde_json::from_str(line)?;
+        Ok(entry.list)
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use super::{Backup, Storage, file};
+use crate::list::List;
+
+pub struct JsonStorage {
+    path: PathBuf,
+    backups: usize,
+}
+
+impl JsonStorage {
+    pub fn new(path: PathBuf, backups: usize) -> Self {
+        JsonStorage { path, backups }
+    }
+}
+
+impl Storage for JsonStorage {
+    fn load(&self) -> Result<Option<List>> {
+        if let Some(contents) = file::read(&self.path)? {
+            return Ok(Some(serde_json::from_str(&contents)?));
+        }
+        Ok(None)
+    }
+
+    fn save(&mut self, list: &List) -> Result<()> {
+        file::write(
+            &self.path,
+            &serde_json::to_string_pretty(list)?,
+            self.backups,
+        )
+    }
+
+    fn list_backups(&self) -> Result<Vec<Backup>> {
+        file::list_backups(&self.path)
+    }
+
+    fn load_backup(&self, backup: &Backup) -> Result<List> {
+        Ok(serde_json::from_str(&file::read_backup(
+            &self.path, backup,
+        )?)?)
+    }
+
+    fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -0,0 +1,55 @@
+mod file;
+mod journal;
+mod json;
+mod yaml;
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use std::path::{Path, PathBuf};
+
+pub use journal::JournalStorage;
+pub use json::JsonStorage;
+pub use yaml::YamlStorage;
+
+use crate::config::{Config, StorageKind};
+use crate::list::List;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub created: NaiveDateTime,
+    // Backend specific handle, a file name or a journal entry
+    pub key: String,
+}
+
+impl std::fmt::Display for Backup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.created.format("%Y-%m-%d %H:%M:%S"))
+    }
+}
+
+pub trait Storage {
+    fn load(&self) -> Result<Option<List>>;
+    fn save(&mut self, list: &List) -> Result<()>;
+    fn list_backups(&self) -> Result<Vec<Backup>>;
+    fn load_backup(&self, backup: &Backup) -> Result<List>;
+    fn path(&self) -> &Path;
+}
+
+pub fn open(config: &Config) -> Box<dyn Storage> {
+    let path = config.data_file();
+    match config.storage {
+        StorageKind::Yaml => Box::new(YamlStorage::new(path, config.backups)),
+        StorageKind::Json => Box::new(JsonStorage::new(path, config.backups)),
+        StorageKind::Journal => Box::new(JournalStorage::new(path, config.backups)),
+    }
+}
+
+// Picks the backend from the file extension, for files outside the config
+pub fn open_path(path: &Path) -> Box<dyn Storage> {
+    let path = PathBuf::from(path);
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => Box::new(JsonStorage::new(path, 0)),
+        // The history lives in the file itself, so keep the usual amount of it
+        Some("journal") => Box::new(JournalStorage::new(path, Config::default().backups)),
+        _ => Box::new(YamlStorage::new(path, 0)),
+    }
+}
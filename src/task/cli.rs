@@ -1,10 +1,13 @@
 use anyhow::Result;
 use chrono::{self, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
-use inquire::{CustomType, DateSelect, Text};
+use inquire::autocompletion::{Autocomplete, Replacement};
+use inquire::{CustomType, CustomUserError, DateSelect, Select, Text};
+use itertools::Itertools;
+use std::collections::BTreeSet;
 
-use crate::task::Task;
+use crate::task::{Priority, Task};
 
-pub fn create_task() -> Task {
+pub fn create_task(known_tags: &BTreeSet<String>) -> Task {
     let name = get_name(None).unwrap();
     let description = get_description(None).unwrap();
     let estimated_time = get_estimated_time(None).unwrap();
@@ -12,7 +15,9 @@ pub fn create_task() -> Task {
     let estimated_stress = get_estimated_stress(None).ok();
     let start = get_datetime(None, true).ok();
     let deadline = get_datetime(None, false).ok();
-    return Task::new(
+    let tags = get_tags(None, known_tags).unwrap();
+    let priority = get_priority(Priority::default()).unwrap();
+    let mut task = Task::new(
         name,
         description,
         TimeDelta::try_hours(estimated_time as i64).unwrap(),
@@ -20,10 +25,13 @@ pub fn create_task() -> Task {
         start,
         deadline,
     );
+    task.set_tags(tags);
+    task.priority = priority;
+    return task;
 }
 
 impl Task {
-    pub fn update_task(&mut self) {
+    pub fn update_task(&mut self, known_tags: &BTreeSet<String>) {
         let name = get_name(Some(self.name.clone())).unwrap();
         let description = get_description(Some(self.description.clone())).unwrap();
         let estimated_time =
@@ -32,12 +40,16 @@ impl Task {
         let estimated_stress = get_estimated_stress(self.estimated_stress).ok();
         let start = get_datetime(self.start, true).ok();
         let deadline = get_datetime(self.deadline, false).ok();
+        let tags = get_tags(Some(&self.tags), known_tags).unwrap();
+        let priority = get_priority(self.priority).unwrap();
         self.name = name;
         self.description = description;
         self.estimated_time = TimeDelta::try_hours(estimated_time as i64).unwrap();
         self.estimated_stress = estimated_stress;
         self.start = start;
         self.deadline = deadline;
+        self.set_tags(tags);
+        self.priority = priority;
     }
 }
 
@@ -117,6 +129,14 @@ fn get_estimated_time(default: Option<usize>) -> Result<usize> {
     return Ok(estimated_time);
 }
 
+fn get_priority(default: Priority) -> Result<Priority> {
+    let priority = Select::new("Priority", Priority::ALL.to_vec())
+        .with_starting_cursor(Priority::ALL.iter().position(|p| *p == default).unwrap())
+        .with_help_message("Higher priorities add stress sooner")
+        .prompt()?;
+    Ok(priority)
+}
+
 fn get_name(default: Option<String>) -> Result<String> {
     let mut name = Text::new("Task Name").with_help_message("Enter the name of the new task");
     if let Some(default) = default.as_ref() {
@@ -134,3 +154,54 @@ fn get_description(default: Option<String>) -> Result<String> {
     let description = description.prompt().unwrap();
     return Ok(description);
 }
+
+// Completes the tag after the last comma from the tags already in use
+#[derive(Clone)]
+struct TagCompleter {
+    known: Vec<String>,
+}
+
+impl TagCompleter {
+    fn split(input: &str) -> (&str, &str) {
+        match input.rfind(',') {
+            Some(index) => (&input[..=index], &input[index + 1..]),
+            None => ("", input),
+        }
+    }
+}
+
+impl Autocomplete for TagCompleter {
+    fn get_suggestions(&mut self, input: &str) -> Result<Vec<String>, CustomUserError> {
+        let (done, current) = TagCompleter::split(input);
+        let current = current.trim().to_lowercase();
+        let entered: Vec<&str> = done.split(',').map(|tag| tag.trim()).collect();
+        Ok(self
+            .known
+            .iter()
+            .filter(|tag| tag.starts_with(&current) && !entered.contains(&tag.as_str()))
+            .map(|tag| format!("{}{}{}", done, if done.is_empty() { "" } else { " " }, tag))
+            .collect())
+    }
+
+    fn get_completion(
+        &mut self,
+        input: &str,
+        highlighted_suggestion: Option<String>,
+    ) -> Result<Replacement, CustomUserError> {
+        Ok(highlighted_suggestion.or_else(|| self.get_suggestions(input).ok()?.into_iter().next()))
+    }
+}
+
+fn get_tags(default: Option<&BTreeSet<String>>, known: &BTreeSet<String>) -> Result<Vec<String>> {
+    let default = default.map(|tags| tags.iter().join(", "));
+    let mut tags = Text::new("Tags")
+        .with_help_message("Comma separated, tab completes existing tags")
+        .with_autocomplete(TagCompleter {
+            known: known.iter().cloned().collect(),
+        });
+    if let Some(default) = default.as_ref() {
+        tags = tags.with_default(default);
+    }
+    let tags = tags.prompt()?;
+    Ok(tags.split(',').map(String::from).collect())
+}
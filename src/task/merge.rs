@@ -0,0 +1,81 @@
+use std::collections::HashSet;
+
+use crate::merge::{Conflict, Resolver, merge_set, merge_value};
+use crate::task::{Priority, Status, Task};
+use itertools::Itertools;
+
+impl Task {
+    // Everything but the hierarchy, which the list merges on its own
+    pub fn fields_eq(&self, other: &Task) -> bool {
+        self.name == other.name
+            && self.description == other.description
+            && self.estimated_time == other.estimated_time
+            && self.estimated_stress == other.estimated_stress
+            && self.start == other.start
+            && self.deadline == other.deadline
+            && self.tags == other.tags
+            && self.priority == other.priority
+            && self.status == other.status
+    }
+
+    pub fn merge(base: Option<&Task>, ours: &Task, theirs: &Task, resolve: &mut Resolver) -> Task {
+        let conflict = |field: &str, ours_value: String, theirs_value: String| Conflict {
+            subject: format!("Task {} \"{}\" {}", ours.id, ours.name, field),
+            ours: ours_value,
+            theirs: theirs_value,
+        };
+        macro_rules! merge_field {
+            ($field:ident, $show:expr) => {
+                merge_value(
+                    base.map(|base| &base.$field),
+                    &ours.$field,
+                    &theirs.$field,
+                    || {
+                        conflict(
+                            stringify!($field),
+                            $show(&ours.$field),
+                            $show(&theirs.$field),
+                        )
+                    },
+                    resolve,
+                )
+            };
+        }
+        let debug = |value: &dyn std::fmt::Debug| format!("{:?}", value);
+        Task {
+            id: ours.id,
+            uid: ours.uid,
+            name: merge_field!(name, |name: &String| name.clone()),
+            description: merge_field!(description, |description: &String| description.clone()),
+            estimated_time: merge_field!(estimated_time, |time: &chrono::TimeDelta| format!(
+                "{} Hours",
+                time.num_hours()
+            )),
+            estimated_stress: merge_field!(estimated_stress, debug),
+            start: merge_field!(start, debug),
+            deadline: merge_field!(deadline, debug),
+            priority: merge_field!(priority, |priority: &Priority| priority.to_string()),
+            status: merge_field!(status, |status: &Status| status.to_string()),
+            status_history: ours
+                .status_history
+                .iter()
+                .chain(theirs.status_history.iter())
+                .cloned()
+                .unique()
+                .sorted_by_key(|change| change.time)
+                .collect(),
+            subtasks: HashSet::new(),
+            supertasks: HashSet::new(),
+            // Tags merge like the hierarchy, additions from both sides are kept
+            tags: merge_set(
+                &base
+                    .map(|base| base.tags.iter().cloned().collect())
+                    .unwrap_or_default(),
+                &ours.tags.iter().cloned().collect(),
+                &theirs.tags.iter().cloned().collect(),
+            )
+            .into_iter()
+            .collect(),
+        }
+    }
+}
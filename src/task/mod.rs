@@ -1,22 +1,45 @@
 use anyhow::Result;
 use chrono::{Local, NaiveDateTime, TimeDelta};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use uuid::Uuid;
 
 pub mod cli;
+mod merge;
+mod priority;
+mod status;
 mod stress;
 
-#[derive(Debug, Serialize, Deserialize)]
+pub use priority::Priority;
+pub use status::{Status, StatusChange};
+pub use stress::StressBreakdown;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
+    // Short number for display and prompts, only unique within one list
     pub id: usize,
+    // Identifies the task across merged, imported and synced lists. Files from
+    // before it existed load as nil until the list assigns one.
+    #[serde(default)]
+    pub uid: Uuid,
     pub name: String,
-    description: String,
-    estimated_time: TimeDelta,
-    estimated_stress: Option<f32>,
+    pub description: String,
+    pub estimated_time: TimeDelta,
+    pub estimated_stress: Option<f32>,
     pub start: Option<NaiveDateTime>,
-    deadline: Option<NaiveDateTime>,
+    pub deadline: Option<NaiveDateTime>,
     pub subtasks: HashSet<usize>,
     pub supertasks: HashSet<usize>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub status: Status,
+    // Every status the task has been set to, oldest first
+    #[serde(default)]
+    pub status_history: Vec<StatusChange>,
 }
 
 impl Task {
@@ -30,6 +53,7 @@ impl Task {
     ) -> Self {
         return Task {
             id: 0,
+            uid: Uuid::new_v4(),
             name,
             description,
             estimated_time,
@@ -38,6 +62,10 @@ impl Task {
             deadline,
             subtasks: HashSet::new(),
             supertasks: HashSet::new(),
+            tags: BTreeSet::new(),
+            priority: Priority::default(),
+            status: Status::default(),
+            status_history: Vec::new(),
         };
     }
 
@@ -45,6 +73,47 @@ impl Task {
         self.id = id;
         return Ok(());
     }
+
+    // Tasks saved before uids existed get one derived from fields every copy
+    // of the old file agrees on, so devices that migrate the same file still
+    // match up when their lists are merged
+    pub fn legacy_uid(&self) -> Uuid {
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_OID,
+            format!("{}\n{}", self.id, self.name).as_bytes(),
+        )
+    }
+
+    // Tags are matched without case, so they're kept lowercase
+    pub fn set_tags<T: AsRef<str>>(&mut self, tags: impl IntoIterator<Item = T>) {
+        self.tags = tags
+            .into_iter()
+            .map(|tag| tag.as_ref().trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+    }
+
+    // Tags saved before they were kept lowercase, true if any changed
+    pub fn migrate_tags(&mut self) -> bool {
+        if self.tags.iter().all(|tag| *tag == tag.to_lowercase()) {
+            return false;
+        }
+        let tags = std::mem::take(&mut self.tags);
+        self.set_tags(tags);
+        true
+    }
+
+    pub fn set_status(&mut self, status: Status) {
+        if self.status == status {
+            return;
+        }
+        self.status = status;
+        self.status_history.push(StatusChange {
+            status,
+            time: Local::now().naive_local(),
+        });
+    }
+
     pub fn started(&self) -> bool {
         let now = Local::now().naive_local();
         let start = if let Some(start) = self.start {
@@ -62,6 +131,12 @@ impl std::fmt::Display for Task {
         writeln!(f, "Name: {}", self.name).unwrap();
         writeln!(f, "Description: {}", self.description).unwrap();
         writeln!(f, "Estimated Hours: {}", self.estimated_time.num_hours()).unwrap();
+        writeln!(f, "Priority: {}", self.priority).unwrap();
+        write!(f, "Status: {}", self.status).unwrap();
+        if let Some(change) = self.status_history.last() {
+            write!(f, " since {}", change.time.format("%Y-%m-%d %H:%M")).unwrap();
+        }
+        writeln!(f).unwrap();
         if let Some(stress) = self.estimated_stress {
             writeln!(f, "Estimated Additional Stress: {}", stress).unwrap();
         }
@@ -71,6 +146,9 @@ impl std::fmt::Display for Task {
         if let Some(deadline) = self.deadline {
             writeln!(f, "Deadline: {:?}", deadline).unwrap();
         }
+        if !self.tags.is_empty() {
+            writeln!(f, "Tags: {}", self.tags.iter().join(", ")).unwrap();
+        }
         write!(f, "")
     }
 }
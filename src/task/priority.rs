@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+// Ordered from least to most important
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 4] = [
+        Priority::Low,
+        Priority::Normal,
+        Priority::High,
+        Priority::Urgent,
+    ];
+
+    // Scales the stress a task produces on its own
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            Priority::Low => 0.5,
+            Priority::Normal => 1.0,
+            Priority::High => 1.5,
+            Priority::Urgent => 2.0,
+        }
+    }
+
+    // How far off a task without a deadline is treated as being
+    pub fn default_hours(&self) -> f32 {
+        match self {
+            Priority::Low => 240.0,
+            Priority::Normal => 120.0,
+            Priority::High => 48.0,
+            Priority::Urgent => 12.0,
+        }
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "Low"),
+            Priority::Normal => write!(f, "Normal"),
+            Priority::High => write!(f, "High"),
+            Priority::Urgent => write!(f, "Urgent"),
+        }
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Priority::ALL
+            .into_iter()
+            .find(|priority| priority.to_string().eq_ignore_ascii_case(value))
+            .ok_or(())
+    }
+}
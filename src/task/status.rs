@@ -0,0 +1,77 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Status {
+    #[default]
+    Todo,
+    InProgress,
+    // Waiting on someone else
+    Waiting,
+    #[serde(alias = "on-hold")]
+    Blocked,
+    // Done and cancelled tasks leave the list, they only show up in the journal
+    Done,
+    Cancelled,
+}
+
+impl Status {
+    pub const ALL: [Status; 6] = [
+        Status::Todo,
+        Status::InProgress,
+        Status::Waiting,
+        Status::Blocked,
+        Status::Done,
+        Status::Cancelled,
+    ];
+
+    // Scales the stress a task produces on its own, waiting on someone else
+    // is a lot less pressing than doing it yourself
+    pub fn stress_factor(&self) -> f32 {
+        match self {
+            Status::Waiting => 0.25,
+            _ => 1.0,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        matches!(self, Status::Done | Status::Cancelled)
+    }
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Todo => write!(f, "Todo"),
+            Status::InProgress => write!(f, "In Progress"),
+            Status::Waiting => write!(f, "Waiting"),
+            Status::Blocked => write!(f, "Blocked"),
+            Status::Done => write!(f, "Done"),
+            Status::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for Status {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.replace(['-', '_', ' '], "");
+        Status::ALL
+            .into_iter()
+            .find(|status| {
+                status
+                    .to_string()
+                    .replace(' ', "")
+                    .eq_ignore_ascii_case(&value)
+            })
+            .ok_or(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub status: Status,
+    pub time: NaiveDateTime,
+}
@@ -6,6 +6,27 @@ fn hours(time: &TimeDelta) -> f32 {
     return time.as_seconds_f32() / 3600.0;
 }
 
+// The pieces stress() is made from, for showing where the number comes from
+#[derive(Debug, Clone, Copy)]
+pub struct StressBreakdown {
+    pub started: bool,
+    pub base: f32,
+    // Estimated stress of the task and everything above it
+    pub inherited: f32,
+    pub hours_til_started: Option<f32>,
+    pub crunch: f32,
+    // Multiplier from the task's priority
+    pub priority: f32,
+    // Multiplier from the task's status
+    pub status: f32,
+    pub effective_hours: f32,
+    // What the task contributes without its subtasks
+    pub own: f32,
+    // Highest stress among the subtasks
+    pub subtasks: f32,
+    pub total: f32,
+}
+
 impl List {
     fn effective_stress(&self, id: usize) -> f32 {
         let parents = self.get_all_parents(id);
@@ -50,7 +71,11 @@ impl List {
     fn crunch_stress(&self, id: usize) -> f32 {
         // This is the hard one...
         // The stress from a task due x hours from now is:
-        let hours = self.hours_til_started(id).unwrap_or(120.0).max(0.0);
+        let priority = self.tasks.get(&id).unwrap().priority;
+        let hours = self
+            .hours_til_started(id)
+            .unwrap_or(priority.default_hours())
+            .max(0.0);
         let f =
             |x: f32| 22.5 * (-1.05 * x).exp() + 2.0 * (-0.05 * x).exp() + 0.5 * (-0.003 * x).exp();
         return f(hours);
@@ -72,8 +97,39 @@ impl List {
             .unwrap_or(0.0);
 
         let hours = hours(&self.effective_time(id));
+        let task = self.tasks.get(&id).unwrap();
+        let factor = task.priority.multiplier() * task.status.stress_factor();
         let stress =
-            self.crunch_stress(id) * (List::base_stess() + self.effective_stress(id)) / hours;
+            factor * self.crunch_stress(id) * (List::base_stess() + self.effective_stress(id))
+                / hours;
         return stress.max(child_stress);
     }
+
+    pub fn stress_breakdown(&self, id: usize) -> StressBreakdown {
+        let task = self.tasks.get(&id).unwrap();
+        let subtasks = task
+            .subtasks
+            .iter()
+            .map(|&x| self.stress(x))
+            .max_by(|a, b| a.partial_cmp(b).unwrap())
+            .unwrap_or(0.0);
+        let effective_hours = hours(&self.effective_time(id));
+        let crunch = self.crunch_stress(id);
+        let inherited = self.effective_stress(id);
+        let priority = task.priority.multiplier();
+        let status = task.status.stress_factor();
+        StressBreakdown {
+            started: task.started(),
+            base: List::base_stess(),
+            inherited,
+            hours_til_started: self.hours_til_started(id),
+            crunch,
+            priority,
+            status,
+            effective_hours,
+            own: priority * status * crunch * (List::base_stess() + inherited) / effective_hours,
+            subtasks,
+            total: self.stress(id),
+        }
+    }
 }
@@ -0,0 +1,174 @@
+use chrono::Local;
+use itertools::Itertools;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{self, Block, ListItem, ListState, Paragraph, Wrap};
+
+use super::App;
+use super::tree::Row;
+use crate::list::List;
+use crate::task::Status;
+
+const HELP: &str = "↑↓ move  ←→ fold  a add  e edit  c complete  x status  s subtasks  p supertasks  t today  u undo  r redo  q quit";
+
+fn names(list: &List, ids: impl Iterator<Item = usize>) -> String {
+    ids.sorted()
+        .map(|id| format!("{} {}", id, list.tasks.get(&id).unwrap().name))
+        .join(", ")
+}
+
+fn row_label(list: &List, row: &Row) -> String {
+    let marker = match (row.has_children, row.collapsed) {
+        (false, _) => " ",
+        (true, true) => "▸",
+        (true, false) => "▾",
+    };
+    let task = list.tasks.get(&row.id).unwrap();
+    let status = if task.status == Status::Todo {
+        String::new()
+    } else {
+        format!(" <{}>", task.status)
+    };
+    format!(
+        "{}{} {} {}{} ({:.2})",
+        "  ".repeat(row.depth),
+        marker,
+        task.id,
+        task.name,
+        status,
+        list.stress(task.id)
+    )
+}
+
+fn draw_tree(app: &App, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = app
+        .rows
+        .iter()
+        .map(|row| ListItem::new(row_label(&app.list, row)))
+        .collect();
+    let tree = widgets::List::new(items)
+        .block(Block::bordered().title(format!(
+            "Tasks (overall stress {:.2})",
+            app.list.total_stress()
+        )))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default();
+    if !app.rows.is_empty() {
+        state.select(Some(app.selected));
+    }
+    frame.render_stateful_widget(tree, area, &mut state);
+}
+
+fn detail_lines(list: &List, id: usize) -> Vec<Line<'static>> {
+    let task = list.tasks.get(&id).unwrap();
+    let mut lines: Vec<Line> = task
+        .to_string()
+        .lines()
+        .map(|line| Line::from(line.to_string()))
+        .collect();
+    lines.push(Line::from(format!(
+        "Subtasks: {}",
+        names(list, task.subtasks.iter().cloned())
+    )));
+    lines.push(Line::from(format!(
+        "Supertasks: {}",
+        names(list, task.supertasks.iter().cloned())
+    )));
+    let earliest = list
+        .schedule
+        .earliest_complete(list.effective_time(id), task.start)
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or(String::from("Not enough scheduled time"));
+    lines.push(Line::from(format!("Earliest Completion: {}", earliest)));
+
+    let stress = list.stress_breakdown(id);
+    lines.push(Line::from(""));
+    lines.push(Line::from("Stress").style(Style::new().add_modifier(Modifier::BOLD)));
+    if !stress.started {
+        lines.push(Line::from("Not started yet, no stress"));
+        return lines;
+    }
+    lines.push(Line::from(format!("Base: {:.2}", stress.base)));
+    lines.push(Line::from(format!(
+        "Estimated (with supertasks): {:.2}",
+        stress.inherited
+    )));
+    let hours_til_started = stress
+        .hours_til_started
+        .map(|hours| format!("{:.1}", hours))
+        .unwrap_or(String::from("no deadline"));
+    lines.push(Line::from(format!(
+        "Hours until it has to start: {}",
+        hours_til_started
+    )));
+    lines.push(Line::from(format!("Crunch: {:.2}", stress.crunch)));
+    lines.push(Line::from(format!("Priority: x{:.1}", stress.priority)));
+    lines.push(Line::from(format!("Status: x{:.2}", stress.status)));
+    lines.push(Line::from(format!(
+        "Hours of work left: {:.1}",
+        stress.effective_hours
+    )));
+    lines.push(Line::from(format!(
+        "Own: priority x status x crunch x (base + estimated) / hours = {:.2}",
+        stress.own
+    )));
+    lines.push(Line::from(format!("Worst subtask: {:.2}", stress.subtasks)));
+    lines.push(Line::from(format!("Total: {:.2}", stress.total)));
+    lines
+}
+
+fn draw_detail(app: &App, frame: &mut Frame, area: Rect) {
+    let lines = match app.selected_id() {
+        Some(id) => detail_lines(&app.list, id),
+        None => vec![Line::from("No tasks, press a to add one")],
+    };
+    let detail = Paragraph::new(lines)
+        .block(Block::bordered().title("Task"))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(detail, area);
+}
+
+fn draw_schedule(app: &App, frame: &mut Frame, area: Rect) {
+    let today = Local::now().naive_local().date();
+    let source = if app.list.schedule.get_scheduled_itinerary(today).is_some() {
+        "scheduled"
+    } else {
+        "default"
+    };
+    let itinerary = app.list.schedule.get_itinerary(today);
+    let mut lines: Vec<Line> = itinerary
+        .timeblocks
+        .iter()
+        .sorted()
+        .map(|block| Line::from(block.to_string().trim_end().to_string()))
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from("Nothing scheduled"));
+    }
+    let schedule = Paragraph::new(lines).block(Block::bordered().title(format!(
+        "Today {} ({})",
+        today.format("%a %Y-%m-%d"),
+        source
+    )));
+    frame.render_widget(schedule, area);
+}
+
+pub fn draw(app: &App, frame: &mut Frame) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+    let [tree, side] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main);
+    let [detail, schedule] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(8)]).areas(side);
+    draw_tree(app, frame, tree);
+    draw_detail(app, frame, detail);
+    draw_schedule(app, frame, schedule);
+    let message = if app.status.is_empty() {
+        HELP
+    } else {
+        &app.status
+    };
+    frame.render_widget(Paragraph::new(message), status);
+}
@@ -0,0 +1,201 @@
+mod draw;
+mod tree;
+
+use anyhow::{Result, anyhow};
+use chrono::Local;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use std::collections::HashSet;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::list::List;
+use crate::manager::{open, save};
+use crate::storage::Storage;
+use crate::task::{Task, cli::create_task};
+use tree::Row;
+
+pub struct App {
+    storage: Box<dyn Storage>,
+    list: List,
+    collapsed: HashSet<usize>,
+    rows: Vec<Row>,
+    selected: usize,
+    stressed: HashSet<usize>,
+    // Replaces the key help until the next key press
+    status: String,
+    quit: bool,
+}
+
+// Hands the terminal back for the inquire prompts the menus already use
+fn suspend<T>(terminal: &mut DefaultTerminal, prompt: impl FnOnce() -> Result<T>) -> Result<T> {
+    ratatui::restore();
+    // Prompts panic when cancelled, that shouldn't take the whole UI down
+    let result =
+        catch_unwind(AssertUnwindSafe(prompt)).unwrap_or_else(|_| Err(anyhow!("Cancelled")));
+    *terminal = ratatui::init();
+    result
+}
+
+impl App {
+    fn new() -> Self {
+        let (storage, list, _) = open();
+        let stressed = list.stressed_tasks();
+        let mut app = App {
+            storage,
+            list,
+            collapsed: HashSet::new(),
+            rows: Vec::new(),
+            selected: 0,
+            stressed,
+            status: String::new(),
+            quit: false,
+        };
+        app.refresh_rows();
+        app
+    }
+
+    fn selected_id(&self) -> Option<usize> {
+        self.rows.get(self.selected).map(|row| row.id)
+    }
+
+    fn refresh_rows(&mut self) {
+        let id = self.selected_id();
+        self.collapsed.retain(|id| self.list.tasks.contains_key(id));
+        self.rows = tree::rows(&self.list, &self.collapsed);
+        // Stay on the same task if it's still there
+        if let Some(index) = id.and_then(|id| self.rows.iter().position(|row| row.id == id)) {
+            self.selected = index;
+        }
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    fn save(&mut self) {
+        self.list.commit();
+        self.stressed = self.list.run_stress_hooks(&self.stressed);
+        save(self.storage.as_mut(), &self.list);
+        self.refresh_rows();
+    }
+
+    fn prompt(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        action: impl FnOnce(&mut List) -> Result<()>,
+    ) {
+        if let Err(error) = suspend(terminal, || action(&mut self.list)) {
+            // Nothing a cancelled or failed prompt recorded is kept
+            self.list.discard();
+            self.status = error.to_string();
+        }
+        self.save();
+    }
+
+    fn handle_key(&mut self, terminal: &mut DefaultTerminal, key: KeyCode) {
+        self.status.clear();
+        let selected = self.selected_id();
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.rows.len().saturating_sub(1))
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                if let Some(id) = selected {
+                    self.collapsed.insert(id);
+                    self.refresh_rows();
+                }
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                if let Some(id) = selected {
+                    self.collapsed.remove(&id);
+                    self.refresh_rows();
+                }
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if let Some(id) = selected {
+                    if !self.collapsed.remove(&id) {
+                        self.collapsed.insert(id);
+                    }
+                    self.refresh_rows();
+                }
+            }
+            KeyCode::Char('a') => self.prompt(terminal, |list| {
+                let task = create_task(&list.all_tags());
+                list.add_task(task);
+                Ok(())
+            }),
+            KeyCode::Char('e') => {
+                if let Some(id) = selected {
+                    self.prompt(terminal, |list| {
+                        list.modify_task(id);
+                        Ok(())
+                    });
+                }
+            }
+            KeyCode::Char('c') => {
+                let task = selected.map(|id| self.list.tasks.get(&id).unwrap());
+                if let Some(task) = task.filter(|task: &&Task| task.started()) {
+                    let id = task.id;
+                    self.prompt(terminal, |list| list.complete_task(id));
+                } else if selected.is_some() {
+                    self.status = String::from("Task hasn't started yet");
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Some(id) = selected {
+                    self.prompt(terminal, |list| list.set_task_status(id));
+                }
+            }
+            KeyCode::Char('s') => {
+                if let Some(id) = selected {
+                    self.prompt(terminal, |list| {
+                        list.update_subtasks(id);
+                        Ok(())
+                    });
+                }
+            }
+            KeyCode::Char('p') => {
+                if let Some(id) = selected {
+                    self.prompt(terminal, |list| {
+                        list.update_supertasks(id);
+                        Ok(())
+                    });
+                }
+            }
+            KeyCode::Char('t') => self.prompt(terminal, |list| {
+                let today = Local::now().naive_local().date();
+                let mut itinerary = list.schedule.get_itinerary(today).clone();
+                itinerary.update();
+                list.set_itinerary(today, itinerary);
+                Ok(())
+            }),
+            KeyCode::Char('u') => {
+                if !self.list.undo() {
+                    self.status = String::from("Nothing to undo");
+                }
+                self.save();
+            }
+            KeyCode::Char('r') => {
+                if !self.list.redo() {
+                    self.status = String::from("Nothing to redo");
+                }
+                self.save();
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn run() -> Result<()> {
+    let mut app = App::new();
+    let mut terminal = ratatui::init();
+    while !app.quit {
+        terminal.draw(|frame| draw::draw(&app, frame))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(&mut terminal, key.code);
+        }
+    }
+    ratatui::restore();
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use itertools::Itertools;
+use std::collections::HashSet;
+
+use crate::list::List;
+
+// One visible line of the task tree
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub id: usize,
+    pub depth: usize,
+    pub has_children: bool,
+    pub collapsed: bool,
+}
+
+// Highest stress first, like the pickers
+fn by_stress(list: &List, ids: impl Iterator<Item = usize>) -> Vec<usize> {
+    ids.map(|id| (id, list.stress(id)))
+        .sorted_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+fn push_rows(
+    list: &List,
+    collapsed: &HashSet<usize>,
+    id: usize,
+    depth: usize,
+    rows: &mut Vec<Row>,
+) {
+    let task = list.tasks.get(&id).unwrap();
+    let is_collapsed = collapsed.contains(&id);
+    rows.push(Row {
+        id,
+        depth,
+        has_children: !task.subtasks.is_empty(),
+        collapsed: is_collapsed,
+    });
+    if is_collapsed {
+        return;
+    }
+    for subtask in by_stress(list, task.subtasks.iter().cloned()) {
+        push_rows(list, collapsed, subtask, depth + 1, rows);
+    }
+}
+
+// Tasks can sit under several supertasks, they show up under each of them
+pub fn rows(list: &List, collapsed: &HashSet<usize>) -> Vec<Row> {
+    let roots = list
+        .tasks
+        .values()
+        .filter(|task| task.supertasks.is_empty())
+        .map(|task| task.id);
+    let mut rows = Vec::new();
+    for id in by_stress(list, roots) {
+        push_rows(list, collapsed, id, 0, &mut rows);
+    }
+    rows
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use chrono::{Datelike, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+use std::fmt::Write;
+
+use super::format_time;
+use crate::list::List;
+use crate::report::{self, Node, ReportOptions};
+
+const LABEL_WIDTH: usize = 28;
+// Days get a tick each when there's room, otherwise only Mondays do
+const DAILY_TICKS: i64 = 30;
+
+struct Chart {
+    begin: NaiveDateTime,
+    span: f64,
+    width: usize,
+}
+
+impl Chart {
+    fn column(&self, time: NaiveDateTime) -> usize {
+        let offset = (time - self.begin).as_seconds_f64() / self.span;
+        ((offset * (self.width - 1) as f64).round().max(0.0) as usize).min(self.width - 1)
+    }
+}
+
+fn flatten<'a>(nodes: &'a [Node], depth: usize, rows: &mut Vec<(usize, &'a Node)>) {
+    for node in nodes {
+        rows.push((depth, node));
+        flatten(&node.children, depth + 1, rows);
+    }
+}
+
+fn label(depth: usize, node: &Node) -> String {
+    let label: String = format!("{}{} {}", "  ".repeat(depth), node.id, node.name)
+        .chars()
+        .take(LABEL_WIDTH)
+        .collect();
+    format!("{:<width$}", label, width = LABEL_WIDTH)
+}
+
+// Bars run from when work can begin to the earliest completion, | marks the
+// deadline. Late tasks are drawn with ! instead of =.
+pub fn gantt(
+    list: &List,
+    subtree: Option<usize>,
+    now: NaiveDateTime,
+    width: usize,
+) -> Result<String> {
+    let report = report::build(
+        list,
+        &ReportOptions {
+            subtree,
+            due_within: None,
+        },
+    )?;
+    let mut rows = Vec::new();
+    flatten(&report.roots, 0, &mut rows);
+
+    let end = rows
+        .iter()
+        .flat_map(|(_, node)| [node.earliest, node.deadline])
+        .flatten()
+        .max()
+        .unwrap_or(now)
+        .max(now + TimeDelta::days(1));
+    let chart = Chart {
+        begin: now,
+        span: (end - now).as_seconds_f64(),
+        width: width.max(10),
+    };
+
+    let mut output = String::new();
+    let (begin_label, end_label) = (format_time(now), format_time(end));
+    writeln!(
+        output,
+        "{:<label$}{}{:>rest$}",
+        "",
+        begin_label,
+        end_label,
+        label = LABEL_WIDTH + 1,
+        rest = chart
+            .width
+            .saturating_sub(begin_label.len())
+            .max(end_label.len() + 1)
+    )
+    .unwrap();
+    let days = (end.date() - now.date()).num_days();
+    let mut ticks = vec!['-'; chart.width];
+    for date in now
+        .date()
+        .succ_opt()
+        .unwrap()
+        .iter_days()
+        .take_while(|date| *date <= end.date())
+    {
+        if days <= DAILY_TICKS || date.weekday() == Weekday::Mon {
+            ticks[chart.column(date.and_time(NaiveTime::MIN))] = '+';
+        }
+    }
+    writeln!(
+        output,
+        "{:<label$} {}",
+        "",
+        ticks.iter().collect::<String>(),
+        label = LABEL_WIDTH
+    )
+    .unwrap();
+
+    for (depth, node) in rows {
+        let task = list.tasks.get(&node.id).unwrap();
+        let start = task.start.unwrap_or(now).max(now);
+        let late = node.late();
+        let mut bar = vec![' '; chart.width];
+        let fill = if late { '!' } else { '=' };
+        match node.earliest {
+            Some(earliest) => {
+                for cell in bar
+                    .iter_mut()
+                    .take(chart.column(earliest) + 1)
+                    .skip(chart.column(start))
+                {
+                    *cell = fill;
+                }
+            }
+            // Not enough scheduled time to ever finish
+            None => {
+                for cell in bar.iter_mut().skip(chart.column(start)) {
+                    *cell = '?';
+                }
+            }
+        }
+        let mut details = Vec::new();
+        if let Some(earliest) = node.earliest {
+            details.push(format!("done {}", format_time(earliest)));
+        }
+        if let Some(deadline) = node.deadline {
+            if deadline < now {
+                bar[0] = '<';
+            } else {
+                bar[chart.column(deadline)] = '|';
+            }
+            details.push(format!("due {}", format_time(deadline)));
+        }
+        if late {
+            details.push(String::from("LATE"));
+        }
+        writeln!(
+            output,
+            "{} {} {}",
+            label(depth, node),
+            bar.iter().collect::<String>(),
+            details.join(", ")
+        )
+        .unwrap();
+    }
+    writeln!(
+        output,
+        "\n= planned work, | deadline, < deadline already passed, ! late, ? not enough scheduled time"
+    )
+    .unwrap();
+    Ok(output)
+}
@@ -0,0 +1,19 @@
+pub mod gantt;
+pub mod today;
+pub mod week;
+
+use chrono::NaiveDateTime;
+
+use crate::list::List;
+use crate::task::Task;
+
+// How many of the most stressful tasks views show
+const TOP_STRESS: usize = 5;
+
+fn format_time(time: NaiveDateTime) -> String {
+    time.format("%Y-%m-%d %H:%M").to_string()
+}
+
+fn label(list: &List, task: &Task) -> String {
+    format!("{} {} ({:.2})", task.id, task.name, list.stress(task.id))
+}
@@ -0,0 +1,102 @@
+use chrono::NaiveDateTime;
+use itertools::Itertools;
+use std::fmt::Write;
+
+use super::{TOP_STRESS, format_time, label};
+use crate::list::List;
+use crate::task::Task;
+
+fn section(agenda: &mut String, title: &str, lines: Vec<String>) {
+    if lines.is_empty() {
+        return;
+    }
+    writeln!(agenda, "\n{}", title).unwrap();
+    for line in lines {
+        writeln!(agenda, "  {}", line).unwrap();
+    }
+}
+
+fn tasks_by<F: Fn(&Task) -> Option<NaiveDateTime>>(
+    list: &List,
+    time: F,
+) -> Vec<(&Task, NaiveDateTime)> {
+    list.tasks
+        .values()
+        .filter_map(|task| time(task).map(|time| (task, time)))
+        .sorted_by_key(|(task, time)| (*time, task.id))
+        .collect()
+}
+
+pub fn today(list: &List, now: NaiveDateTime) -> String {
+    let today = now.date();
+    let mut agenda = String::new();
+    let source = if list.schedule.get_scheduled_itinerary(today).is_some() {
+        "scheduled"
+    } else {
+        "default"
+    };
+    writeln!(
+        agenda,
+        "{} ({} schedule), overall stress {:.2}",
+        today.format("%A %Y-%m-%d"),
+        source,
+        list.total_stress()
+    )
+    .unwrap();
+
+    let blocks = list
+        .schedule
+        .get_itinerary(today)
+        .timeblocks
+        .iter()
+        .sorted()
+        .map(|block| {
+            format!(
+                "{} - {}",
+                block.start.format("%H:%M"),
+                block.end.format("%H:%M")
+            )
+        })
+        .collect_vec();
+    if blocks.is_empty() {
+        writeln!(agenda, "\nNo time scheduled today").unwrap();
+    }
+    section(&mut agenda, "Schedule", blocks);
+
+    let starting = tasks_by(list, |task| {
+        task.start.filter(|start| start.date() == today)
+    })
+    .into_iter()
+    .map(|(task, start)| format!("{} at {}", label(list, task), start.format("%H:%M")))
+    .collect();
+    section(&mut agenda, "Starting today", starting);
+
+    let due = tasks_by(list, |task| {
+        task.deadline
+            .filter(|deadline| deadline.date() == today && *deadline >= now)
+    })
+    .into_iter()
+    .map(|(task, deadline)| format!("{} at {}", label(list, task), deadline.format("%H:%M")))
+    .collect();
+    section(&mut agenda, "Due today", due);
+
+    let overdue = tasks_by(list, |task| {
+        task.deadline.filter(|deadline| *deadline < now)
+    })
+    .into_iter()
+    .map(|(task, deadline)| format!("{} was due {}", label(list, task), format_time(deadline)))
+    .collect();
+    section(&mut agenda, "Overdue", overdue);
+
+    let stressful = list
+        .tasks
+        .values()
+        .map(|task| (task, list.stress(task.id)))
+        .filter(|(_, stress)| *stress > 0.0)
+        .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+        .take(TOP_STRESS)
+        .map(|(task, _)| label(list, task))
+        .collect();
+    section(&mut agenda, "Most stressful", stressful);
+    agenda
+}
@@ -0,0 +1,153 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Timelike};
+use itertools::Itertools;
+use std::fmt::Write;
+
+use super::format_time;
+use crate::list::List;
+
+// Each grid row covers this many minutes
+const SLOT_MINUTES: u32 = 30;
+const CELL_WIDTH: usize = 11;
+// Hours always shown, even when the week has nothing outside them
+const DAY_START: u32 = 8;
+const DAY_END: u32 = 18;
+
+struct Marker {
+    time: NaiveDateTime,
+    code: String,
+    text: String,
+}
+
+fn markers(list: &List, first: NaiveDate, last: NaiveDate) -> Vec<Marker> {
+    let in_week = |time: &NaiveDateTime| time.date() >= first && time.date() <= last;
+    let mut markers = Vec::new();
+    for task in list.tasks.values() {
+        if let Some(start) = task.start.filter(in_week) {
+            markers.push(Marker {
+                time: start,
+                code: format!("S{}", task.id),
+                text: format!("{} starts", task.name),
+            });
+        }
+        if let Some(deadline) = task.deadline.filter(in_week) {
+            markers.push(Marker {
+                time: deadline,
+                code: format!("D{}", task.id),
+                text: format!("{} due", task.name),
+            });
+        }
+    }
+    markers.sort_by(|a, b| a.time.cmp(&b.time).then(a.code.cmp(&b.code)));
+    markers
+}
+
+fn slot(time: NaiveTime) -> u32 {
+    (time.hour() * 60 + time.minute()) / SLOT_MINUTES
+}
+
+fn cell(text: &str, fill: char) -> String {
+    let mut cell: String = text.chars().take(CELL_WIDTH).collect();
+    while cell.chars().count() < CELL_WIDTH {
+        cell.push(fill);
+    }
+    cell
+}
+
+// The week containing date, Monday first
+pub fn week(list: &List, date: NaiveDate) -> String {
+    let first = date - TimeDelta::days(date.weekday().num_days_from_monday() as i64);
+    let days = first.iter_days().take(7).collect_vec();
+    let last = days[6];
+    let markers = markers(list, first, last);
+
+    let slots_per_day = 24 * 60 / SLOT_MINUTES;
+    let mut first_slot = DAY_START * 60 / SLOT_MINUTES;
+    let mut last_slot = DAY_END * 60 / SLOT_MINUTES;
+    for day in days.iter() {
+        for block in list.schedule.get_itinerary(*day).timeblocks.iter() {
+            first_slot = first_slot.min(slot(block.start));
+            // A block ending at midnight runs to the end of the day
+            let end = if block.end == NaiveTime::MIN {
+                slots_per_day
+            } else {
+                slot(block.end - TimeDelta::minutes(1)) + 1
+            };
+            last_slot = last_slot.max(end);
+        }
+    }
+    for marker in markers.iter() {
+        first_slot = first_slot.min(slot(marker.time.time()));
+        last_slot = last_slot.max(slot(marker.time.time()) + 1);
+    }
+
+    let mut grid = String::new();
+    let header = days
+        .iter()
+        .map(|day| cell(&day.format("%a %m-%d").to_string(), ' '))
+        .join("|");
+    writeln!(grid, "      |{}|", header).unwrap();
+    let sources = days
+        .iter()
+        .map(|&day| {
+            if list.schedule.get_scheduled_itinerary(day).is_some() {
+                cell("scheduled", ' ')
+            } else {
+                cell("default", ' ')
+            }
+        })
+        .join("|");
+    writeln!(grid, "      |{}|", sources).unwrap();
+    writeln!(
+        grid,
+        "------+{}+",
+        vec!["-".repeat(CELL_WIDTH); 7].join("+")
+    )
+    .unwrap();
+
+    for slot in first_slot..last_slot {
+        let time =
+            NaiveTime::from_hms_opt(slot * SLOT_MINUTES / 60, slot * SLOT_MINUTES % 60, 0).unwrap();
+        let slot_end = time + TimeDelta::minutes(SLOT_MINUTES as i64);
+        let cells = days
+            .iter()
+            .map(|&day| {
+                let free = list
+                    .schedule
+                    .get_itinerary(day)
+                    .timeblocks
+                    .iter()
+                    .any(|block| {
+                        block.start <= time && (time < block.end || block.end == NaiveTime::MIN)
+                    });
+                let codes = markers
+                    .iter()
+                    .filter(|marker| {
+                        marker.time.date() == day
+                            && marker.time.time() >= time
+                            && (marker.time.time() < slot_end || slot_end == NaiveTime::MIN)
+                    })
+                    .map(|marker| marker.code.as_str())
+                    .join(",");
+                cell(&codes, if free { '#' } else { ' ' })
+            })
+            .join("|");
+        writeln!(grid, "{} |{}|", time.format("%H:%M"), cells).unwrap();
+    }
+
+    writeln!(
+        grid,
+        "\n# scheduled time, S<id> task starts, D<id> task due"
+    )
+    .unwrap();
+    for marker in markers {
+        writeln!(
+            grid,
+            "  {:<5} {} {}",
+            marker.code,
+            format_time(marker.time),
+            marker.text
+        )
+        .unwrap();
+    }
+    grid
+}